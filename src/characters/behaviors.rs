@@ -0,0 +1,263 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::math::vec2;
+use bevy::prelude::*;
+use bevy_text_mode::TextModeTextureAtlasSprite;
+
+use crate::{collision, GameState, MainBundle};
+use crate::characters::monsters::{Families, Monster, Monsters, spawn_monster};
+use crate::collision::{BodyType, Damage, SolidBody};
+use crate::graphics::camera::CameraEffects;
+use crate::graphics::tiles::Tiles;
+use crate::rounds::MovementTypes;
+use crate::screens::survival::SurvivalUI;
+use crate::screens::Textures;
+use crate::weapons::spawn_enemy_homing_shot;
+use crate::util::{behaviors, is_oob, Palette, z_pos};
+use crate::util::size::{FRAME_BOTTOM, FRAME_TOP, tile_to_f32};
+
+/// Per-kind monster behaviors, as components attached by [spawn_monster] (see [attach]).
+///
+/// - [Splits]: breaks into smaller copies on death
+/// - [Summons]: periodically calls [Minion]s
+/// - [FlameTrail]: leaves lingering flames hurting the ship
+/// - [Bursts]: moves along its path in quick bursts
+/// - [Dodges]: steps aside when a ship shot comes close
+/// - [FiresSeekers]: periodically fires shots homing on the ship
+pub struct BehaviorsPlugin;
+
+impl Plugin for BehaviorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (update_bursts, dodge_shots, summon_minions, drop_flames, update_flames, fire_seekers)
+                .in_set(OnUpdate(GameState::Survival))
+        );
+    }
+}
+
+/// Monster spawned by another monster, it flies through instead of settling in front of the ship.
+#[derive(Component)]
+pub struct Minion;
+
+/// Splits into two [Monsters::SmallBlob] with half the lives and the same modifiers, [generations] times.
+#[derive(Component)]
+pub struct Splits {
+    generations: u8,
+}
+
+#[derive(Component, Default)]
+pub struct Summons {
+    frame: usize,
+    summoned: usize,
+}
+
+#[derive(Component, Default)]
+pub struct FlameTrail {
+    frame: usize,
+}
+
+#[derive(Component, Default)]
+pub struct Bursts {
+    frame: usize,
+}
+
+impl Bursts {
+    /// Path time elapsed this frame: fast during a burst, 0 in between.
+    pub fn time_step(&self) -> f32 {
+        if self.frame % behaviors::BURST_PERIOD < behaviors::BURST_FRAMES {
+            behaviors::BURST_PERIOD as f32 / behaviors::BURST_FRAMES as f32
+        } else { 0. }
+    }
+}
+
+#[derive(Component, Default)]
+pub struct Dodges {
+    cooldown: usize,
+    remaining: usize,
+    direction: f32,
+}
+
+#[derive(Component, Default)]
+pub struct FiresSeekers {
+    frame: usize,
+}
+
+/// Lingering hazard left by a [FlameTrail].
+#[derive(Component)]
+struct Flame {
+    frame: usize,
+}
+
+/// Attaches the behaviors of [kind] to a monster being spawned.
+pub fn attach(entity: &mut EntityCommands, kind: Monsters) {
+    match kind {
+        Monsters::Blob => { entity.insert(Splits { generations: behaviors::SPLIT_GENERATIONS }); }
+        Monsters::Necromancer => { entity.insert(Summons::default()); }
+        Monsters::MagicCandle => { entity.insert(FlameTrail::default()); }
+        Monsters::SpaceCrab => { entity.insert(Bursts::default()); }
+        Monsters::StarFly => { entity.insert(Dodges::default()); }
+        Monsters::SuperEye => { entity.insert(FiresSeekers::default()); }
+        _ => {}
+    }
+}
+
+/// Spawns the two halves of a dying [monster] which [Splits].
+pub fn split(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    monster: &Monster,
+    pos: &Transform,
+    splits: &Splits,
+) {
+    let lives = (monster.max_lives / 2).max(1);
+    // Halves are centered on the monster, one above the other
+    let origin = pos.translation.truncate()
+        + (collision::body_size(monster.kind.sprite()) - collision::body_size(Monsters::SmallBlob.sprite())) / 2.;
+    for dy in [-1., 1.] {
+        let (x, y) = (origin.x.round(), origin.y.round() + dy * tile_to_f32(behaviors::SPLIT_DISTANCE));
+        let mut half = Monster::new(Monsters::SmallBlob, MovementTypes::SineSmall.to_path(monster.side), x, y, monster.side);
+        half.lives = lives;
+        half.max_lives = lives;
+        half.modifiers = monster.modifiers;
+        let id = spawn_monster(commands, atlas, half, Families::Color(Palette::random()), x, y);
+        let mut entity = commands.entity(id);
+        entity.insert(Minion);
+        if splits.generations > 1 { entity.insert(Splits { generations: splits.generations - 1 }); }
+    }
+}
+
+fn update_bursts(
+    mut monsters: Query<&mut Bursts>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for mut bursts in monsters.iter_mut() {
+        bursts.frame += 1;
+    }
+}
+
+fn dodge_shots(
+    mut monsters: Query<(&mut Monster, &mut Dodges, &SolidBody, &Transform)>,
+    shots: Query<(&SolidBody, &Transform), Without<Monster>>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for (mut monster, mut dodges, body, pos) in monsters.iter_mut() {
+        if dodges.remaining > 0 {
+            dodges.remaining -= 1;
+            monster.offset.y += dodges.direction * behaviors::DODGE_SPEED;
+            continue;
+        }
+        if dodges.cooldown > 0 {
+            dodges.cooldown -= 1;
+            continue;
+        }
+
+        let center = body.center(pos.translation);
+        let incoming = shots.iter()
+            .filter(|(shot_body, _)| shot_body.body_type == BodyType::ShipShot)
+            .map(|(shot_body, shot_pos)| shot_body.center(shot_pos.translation))
+            .find(|shot| (shot.x - center.x).abs() < behaviors::DODGE_DISTANCE && (shot.y - center.y).abs() < body.height);
+        let Some(shot) = incoming else { continue };
+
+        // Step away from the shot, unless it would leave the frame
+        let mut direction = if shot.y < center.y { 1. } else { -1. };
+        let travel = behaviors::DODGE_SPEED * behaviors::DODGE_FRAMES as f32;
+        if center.y + direction * travel + body.height / 2. > tile_to_f32(FRAME_TOP)
+            || center.y + direction * travel - body.height / 2. < tile_to_f32(FRAME_BOTTOM) {
+            direction = -direction;
+        }
+        dodges.direction = direction;
+        dodges.remaining = behaviors::DODGE_FRAMES;
+        dodges.cooldown = behaviors::DODGE_COOLDOWN;
+    }
+}
+
+fn summon_minions(
+    mut commands: Commands,
+    mut summoners: Query<(&Monster, &mut Summons, &Transform)>,
+    textures: Res<Textures>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for (monster, mut summons, pos) in summoners.iter_mut() {
+        if monster.lives <= 0 || is_oob(pos) || summons.summoned >= behaviors::SUMMON_MAX { continue; }
+        summons.frame += 1;
+        if summons.frame % behaviors::SUMMON_INTERVAL != 0 { continue; }
+
+        summons.summoned += 1;
+        let (x, y) = (pos.translation.x, pos.translation.y);
+        let mut minion = Monster::new(behaviors::SUMMONED, MovementTypes::Straight.to_path(monster.side), x, y, monster.side);
+        minion.lives = behaviors::SUMMON_HP;
+        minion.max_lives = behaviors::SUMMON_HP;
+        let id = spawn_monster(&mut commands, &textures.mrmotext, minion, Families::Color(behaviors::SUMMON_COLOR), x, y);
+        commands.entity(id).insert(Minion);
+    }
+}
+
+fn drop_flames(
+    mut commands: Commands,
+    mut candles: Query<(&Monster, &mut FlameTrail, &SolidBody, &Transform)>,
+    textures: Res<Textures>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for (monster, mut trail, body, pos) in candles.iter_mut() {
+        if monster.lives <= 0 || is_oob(pos) { continue; }
+        trail.frame += 1;
+        if trail.frame % behaviors::FLAME_INTERVAL != 0 { continue; }
+
+        let tile = Tiles::Flame.to_tile().with_fg(behaviors::FLAME_COLOR);
+        let origin = body.center(pos.translation) - vec2(tile_to_f32(1), tile_to_f32(1)) / 2.;
+        commands
+            .spawn(Flame { frame: 0 })
+            .insert(MainBundle::from_xyz(origin.x.round(), origin.y.round(), z_pos::SHOTS))
+            .insert(SolidBody {
+                body_type: BodyType::EnemyShot,
+                width: tile_to_f32(1),
+                height: tile_to_f32(1),
+                bottom_right_anchor: false,
+            })
+            .insert(Damage(1))
+            .insert(SurvivalUI)
+            .with_children(|builder| { builder.spawn(tile.sprite(0, 0, 0., &textures.mrmotext)); });
+    }
+}
+
+fn update_flames(
+    mut commands: Commands,
+    mut flames: Query<(&mut Flame, Entity)>,
+    mut tiles: Query<&mut TextModeTextureAtlasSprite>,
+    children_query: Query<&Children>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for (mut flame, id) in flames.iter_mut() {
+        flame.frame += 1;
+        if flame.frame >= behaviors::FLAME_LIFETIME {
+            commands.entity(id).despawn_recursive();
+            continue;
+        }
+
+        let alpha = (behaviors::FLAME_LIFETIME - flame.frame) as f32 / behaviors::FLAME_FADE as f32;
+        for child in children_query.iter_descendants(id) {
+            if let Ok(mut sprite) = tiles.get_mut(child) { sprite.alpha = alpha.min(1.); }
+        }
+    }
+}
+
+fn fire_seekers(
+    mut commands: Commands,
+    mut monsters: Query<(&Monster, &mut FiresSeekers, &SolidBody, &Transform)>,
+    textures: Res<Textures>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for (monster, mut seekers, body, pos) in monsters.iter_mut() {
+        if monster.lives <= 0 || is_oob(pos) { continue; }
+        seekers.frame += 1;
+        if seekers.frame % behaviors::SEEKER_INTERVAL != 0 { continue; }
+
+        let origin = body.center(pos.translation) - vec2(tile_to_f32(1), tile_to_f32(1)) / 2.;
+        spawn_enemy_homing_shot(&mut commands, &textures.mrmotext, origin, monster.side);
+    }
+}
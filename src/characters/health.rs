@@ -0,0 +1,253 @@
+use bevy::math::vec2;
+use bevy::prelude::*;
+use bevy_text_mode::TextModeTextureAtlasSprite;
+
+use crate::GameState;
+use crate::characters::monsters::Monster;
+use crate::collision::SolidBody;
+use crate::graphics::animation::NoAnimation;
+use crate::graphics::camera::CameraEffects;
+use crate::graphics::text::glyph_index;
+use crate::screens::survival::SurvivalUI;
+use crate::screens::Textures;
+use crate::util::{health, misc, Palette, sprite, z_pos};
+use crate::util::size::tile_to_f32;
+
+/// Shows how much damage monsters take.
+///
+/// - Monsters with more than [health::BAR_MIN_HP] lives get a health bar under them, toggled with [health::TOGGLE_KEY]
+/// - Damage numbers rise from monsters hit by the ship shots, and fade out
+/// - Monsters hit flash and recoil in the direction of the shot, more so on the killing blow
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(HealthBars(true))
+            .add_systems(
+                (toggle_health_bars, spawn_health_bars.after(toggle_health_bars), update_health_bars.after(spawn_health_bars), update_damage_numbers,
+                 update_hit_flashes, update_recoils)
+                    .in_set(OnUpdate(GameState::Survival))
+            );
+    }
+}
+
+/// Whether health bars are shown.
+#[derive(Resource)]
+pub struct HealthBars(pub bool);
+
+/// The monster has a health bar.
+#[derive(Component)]
+struct HasHealthBar;
+
+/// Rectangle drawn under [monster], [fill] is the part showing the lives left.
+/// Bars aren't children of the monster so that they don't get hitboxes.
+#[derive(Component)]
+struct HealthBar {
+    monster: Entity,
+    fill: bool,
+}
+
+#[derive(Component)]
+struct DamageNumber {
+    frame: usize,
+}
+
+/// Tints every tile of the monster for a few frames after a hit.
+/// [colors] keeps the original (tile, fg, bg) to restore them.
+#[derive(Component)]
+pub struct HitFlash {
+    frame: usize,
+    killing: bool,
+    colors: Vec<(Entity, Color, Color)>,
+}
+
+impl HitFlash {
+    pub fn new(killing: bool) -> Self {
+        Self { frame: 0, killing, colors: vec![] }
+    }
+
+    fn duration(&self) -> usize {
+        if self.killing { health::KILL_FLASH } else { health::HIT_FLASH }
+    }
+}
+
+/// Pushes the monster by [distance] pixels along x, then brings it back.
+/// Monsters are frozen after a hit, so the displacement is applied to their transform directly.
+#[derive(Component)]
+pub struct Recoil {
+    frame: usize,
+    distance: f32,
+}
+
+impl Recoil {
+    /// [direction] is the direction of the shot along x (-1, 0 or 1).
+    pub fn new(direction: f32, killing: bool) -> Self {
+        let distance = if killing { health::KILL_RECOIL } else { health::RECOIL };
+        Self { frame: 0, distance: direction * distance }
+    }
+
+    fn displacement(&self, frame: usize) -> f32 {
+        self.distance * (1. - frame as f32 / health::RECOIL_FRAMES as f32).max(0.)
+    }
+}
+
+fn toggle_health_bars(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut health_bars: ResMut<HealthBars>,
+    monsters: Query<Entity, With<HasHealthBar>>,
+    bars: Query<Entity, With<HealthBar>>,
+) {
+    if !keys.just_pressed(health::TOGGLE_KEY) { return; }
+
+    health_bars.0 = !health_bars.0;
+    if !health_bars.0 {
+        for e in &monsters { commands.entity(e).remove::<HasHealthBar>(); }
+        for e in &bars { commands.entity(e).despawn_recursive(); }
+    }
+}
+
+fn spawn_health_bars(
+    mut commands: Commands,
+    health_bars: Res<HealthBars>,
+    monsters: Query<(&Monster, Entity), (With<SolidBody>, Without<HasHealthBar>)>,
+    textures: Res<Textures>,
+) {
+    if !health_bars.0 { return; }
+
+    for (monster, id) in monsters.iter() {
+        if monster.max_lives <= health::BAR_MIN_HP { continue; }
+        commands.entity(id).insert(HasHealthBar);
+        for (fill, color, z) in [(false, health::BAR_BG, 0.), (true, health::BAR_FG, 0.1)] {
+            let mut bundle = sprite(
+                misc::BLOCK_TILE, 0, 0, z_pos::HEALTH_BARS + z,
+                Palette::Transparent, color,
+                false, 0,
+                textures.mrmotext.clone(),
+            );
+            // Hidden until positioned under the monster
+            bundle.sprite.alpha = 0.;
+            commands
+                .spawn(bundle)
+                .insert(HealthBar { monster: id, fill })
+                .insert(NoAnimation)
+                .insert(SurvivalUI);
+        }
+    }
+}
+
+fn update_health_bars(
+    mut commands: Commands,
+    mut bars: Query<(&HealthBar, &mut Transform, &mut TextModeTextureAtlasSprite, Entity), Without<Monster>>,
+    monsters: Query<(&Monster, &SolidBody, &Transform)>,
+) {
+    for (bar, mut pos, mut sprite, id) in bars.iter_mut() {
+        let Ok((monster, body, monster_pos)) = monsters.get(bar.monster) else {
+            commands.entity(id).despawn_recursive();
+            continue;
+        };
+
+        let origin = body.bottom_left(monster_pos.translation) - vec2(0., health::BAR_OFFSET + health::BAR_HEIGHT);
+        let ratio = if bar.fill { (monster.lives.max(0) as f32 / monster.max_lives as f32).min(1.) } else { 1. };
+        pos.translation.x = origin.x;
+        pos.translation.y = origin.y;
+        pos.scale = Vec3::new((body.width * ratio).round() / tile_to_f32(1), health::BAR_HEIGHT / tile_to_f32(1), 1.);
+        sprite.alpha = 1.;
+    }
+}
+
+/// Spawns the digits of [damage] centered on [point], rising and fading out.
+pub fn spawn_damage_number(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    damage: i16,
+    point: Vec2,
+) {
+    let digits = damage.to_string();
+    let left = point.x - tile_to_f32(digits.len()) / 2.;
+    for (i, digit) in digits.chars().enumerate() {
+        let Some(index) = glyph_index(digit) else { continue };
+        let mut bundle = sprite(
+            index, 0, 0, z_pos::DAMAGE_NUMBERS,
+            Palette::Transparent, health::NUMBER_COLOR,
+            false, 0,
+            atlas.clone(),
+        );
+        bundle.transform.translation.x = (left + tile_to_f32(i)).round();
+        bundle.transform.translation.y = point.y.round();
+        commands
+            .spawn(bundle)
+            .insert(DamageNumber { frame: 0 })
+            .insert(NoAnimation)
+            .insert(SurvivalUI);
+    }
+}
+
+fn update_damage_numbers(
+    mut commands: Commands,
+    mut numbers: Query<(&mut DamageNumber, &mut Transform, &mut TextModeTextureAtlasSprite, Entity)>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for (mut number, mut pos, mut sprite, id) in numbers.iter_mut() {
+        number.frame += 1;
+        if number.frame >= health::NUMBER_LIFETIME {
+            commands.entity(id).despawn_recursive();
+            continue;
+        }
+
+        // Rise by whole pixels
+        if number.frame % health::NUMBER_RISE_INTERVAL == 0 { pos.translation.y += 1.; }
+        sprite.alpha = 1. - number.frame as f32 / health::NUMBER_LIFETIME as f32;
+    }
+}
+
+fn update_hit_flashes(
+    mut commands: Commands,
+    mut flashes: Query<(&mut HitFlash, Entity)>,
+    mut tiles: Query<&mut TextModeTextureAtlasSprite>,
+    children_query: Query<&Children>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    let transparent: Color = Palette::Transparent.into();
+    for (mut flash, id) in flashes.iter_mut() {
+        if flash.frame == 0 {
+            let color: Color = if flash.killing { health::KILL_FLASH_COLOR.into() } else { health::HIT_FLASH_COLOR.into() };
+            for child in children_query.iter_descendants(id) {
+                let Ok(mut sprite) = tiles.get_mut(child) else { continue };
+                flash.colors.push((child, sprite.fg, sprite.bg));
+                sprite.fg = color;
+                if sprite.bg != transparent { sprite.bg = color; }
+            }
+        }
+
+        flash.frame += 1;
+        if flash.frame >= flash.duration() {
+            for &(tile, fg, bg) in flash.colors.iter() {
+                if let Ok(mut sprite) = tiles.get_mut(tile) {
+                    sprite.fg = fg;
+                    sprite.bg = bg;
+                }
+            }
+            commands.entity(id).remove::<HitFlash>();
+        }
+    }
+}
+
+fn update_recoils(
+    mut commands: Commands,
+    mut monsters: Query<(&mut Recoil, &mut Transform, Entity)>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for (mut recoil, mut pos, id) in monsters.iter_mut() {
+        // Kicked on the first frame, then back linearly
+        let previous = if recoil.frame == 0 { 0. } else { recoil.displacement(recoil.frame - 1) };
+        pos.translation.x += recoil.displacement(recoil.frame) - previous;
+
+        recoil.frame += 1;
+        if recoil.frame > health::RECOIL_FRAMES { commands.entity(id).remove::<Recoil>(); }
+    }
+}
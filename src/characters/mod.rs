@@ -1,2 +1,4 @@
 pub mod ship;
 pub mod monsters;
+pub mod health;
+pub mod behaviors;
@@ -2,18 +2,30 @@ use bevy::asset::Handle;
 use bevy::math::vec2;
 use bevy::prelude::*;
 use bevy::sprite::TextureAtlas;
+use bevy_text_mode::TextModeTextureAtlasSprite;
 use rand::prelude::SliceRandom;
+use rand::Rng;
 use strum_macros::EnumIter;
 
 use crate::{collision, MainBundle, util};
-use crate::collision::{BodyType, Invincible, SolidBody};
-use crate::graphics::sprites;
+use crate::characters::behaviors;
+use crate::characters::behaviors::{Bursts, Minion, split, Splits};
+use crate::collision::{BodyType, Damage, Invincible, SolidBody};
+use crate::graphics::{debris, sprites};
+use crate::graphics::camera::CameraEffects;
 use crate::graphics::sprites::{RTEMO_PALETTE, TILE};
+use crate::pickups::{drop_pickup, Pickups};
+use crate::weapons::BlocksLasers;
+use crate::progress::Unlocks;
 use crate::rounds::{CurrentRound, MovementTypes};
 use crate::screens::survival::SurvivalUI;
-use crate::util::{Palette, Side, z_pos};
+use crate::screens::Textures;
+use crate::util::{camera, effects, is_oob, modifiers, Palette, Side, z_pos};
 use crate::util::size::{tile_to_f32, WIDTH};
 
+const BOSS_DROPS: &[(Option<Pickups>, u32)] = &[(Some(Pickups::PowerUp), 3), (Some(Pickups::Heart), 2), (Some(Pickups::Bomb), 1)];
+const MINION_DROPS: &[(Option<Pickups>, u32)] = &[(None, 12), (Some(Pickups::Gem), 3), (Some(Pickups::Heart), 1)];
+
 #[derive(Debug, EnumIter, Copy, Clone)]
 pub enum Monsters {
     CashKnight,
@@ -29,6 +41,8 @@ pub enum Monsters {
     Shroom,
     Fox,
     Blob,
+    /// Half of a [Monsters::Blob], only spawned when it splits
+    SmallBlob,
 }
 
 impl Monsters {
@@ -47,6 +61,25 @@ impl Monsters {
         }
     }
 
+    /// Weighted drop table, [None] for no drop.
+    pub fn drops(&self) -> &'static [(Option<Pickups>, u32)] {
+        if self.is_boss() {
+            BOSS_DROPS
+        } else {
+            match self {
+                Monsters::StarFly | Monsters::Bat | Monsters::Shroom => &[
+                    (None, 12), (Some(Pickups::Gem), 3), (Some(Pickups::Heart), 1),
+                ],
+                Monsters::Fox | Monsters::MrCactus | Monsters::Necromancer => &[
+                    (None, 6), (Some(Pickups::Gem), 3), (Some(Pickups::PowerUp), 2), (Some(Pickups::Shield), 1), (Some(Pickups::Bomb), 1),
+                ],
+                _ => &[
+                    (None, 10), (Some(Pickups::Gem), 3), (Some(Pickups::Heart), 1), (Some(Pickups::PowerUp), 1), (Some(Pickups::Shield), 1), (Some(Pickups::Bomb), 1),
+                ],
+            }
+        }
+    }
+
     pub fn sprite(&self) -> &[TILE] {
         match self {
             Monsters::CashKnight => &sprites::CASH_KNIGHT,
@@ -62,6 +95,7 @@ impl Monsters {
             Monsters::Shroom => &sprites::SHROOM,
             Monsters::Fox => &sprites::FOX,
             Monsters::Blob => &sprites::BLOB,
+            Monsters::SmallBlob => &sprites::SMALL_BLOB,
         }
     }
 
@@ -74,7 +108,7 @@ impl Monsters {
             Monsters::SpaceCrab => vec![Palette::Transparent, Palette::Black, Palette::Lava],
             Monsters::SpaceShrimp => vec![Palette::Transparent, Palette::Black, Palette::Blue],
             Monsters::SuperEye => vec![Palette::Transparent, Palette::Black, Palette::Blue],
-            Monsters::Skulleton | Monsters::Bat | Monsters::MagicCandle | Monsters::Shroom | Monsters::Fox | Monsters::Blob => RTEMO_PALETTE.iter().map(|p| *p).collect::<Vec<Palette>>()
+            Monsters::Skulleton | Monsters::Bat | Monsters::MagicCandle | Monsters::Shroom | Monsters::Fox | Monsters::Blob | Monsters::SmallBlob => RTEMO_PALETTE.iter().map(|p| *p).collect::<Vec<Palette>>()
         }
     }
 
@@ -93,10 +127,46 @@ impl Monsters {
             Monsters::Shroom => 3,
             Monsters::Fox => 30,
             Monsters::Blob => 5,
+            Monsters::SmallBlob => 2,
         }
     }
 }
 
+/// Optional traits applicable to any monster.
+#[derive(Copy, Clone, Default)]
+pub struct Modifiers {
+    /// Damage taken per hit is reduced by [armor], down to 1
+    pub armor: i16,
+    /// Shots hitting the side facing the ship are blocked, lasers stop on the monster
+    pub front_shield: bool,
+    /// Frames to regain a life, 0 if the monster doesn't regenerate
+    pub regeneration: usize,
+    /// More lives, a distinct palette and better drops
+    pub elite: bool,
+}
+
+impl Modifiers {
+    /// Rolls each modifier with its own chance.
+    pub fn random() -> Self {
+        let mut rng = rand::thread_rng();
+        Self {
+            armor: if rng.gen_bool(modifiers::ARMOR_CHANCE) { modifiers::ARMOR } else { 0 },
+            front_shield: rng.gen_bool(modifiers::FRONT_SHIELD_CHANCE),
+            regeneration: if rng.gen_bool(modifiers::REGENERATION_CHANCE) { modifiers::REGENERATION_INTERVAL } else { 0 },
+            elite: rng.gen_bool(modifiers::ELITE_CHANCE),
+        }
+    }
+
+    /// Only the elite modifier, for scripted spawns.
+    pub fn elite() -> Self {
+        Self { elite: true, ..default() }
+    }
+}
+
+/// Frames since the monster last regained a life.
+#[derive(Component, Default)]
+pub struct Regeneration(usize);
+
 #[derive(Copy, Clone)]
 pub enum Families {
     Bats,
@@ -123,27 +193,40 @@ pub fn spawn_monster(
     y: f32,
 ) -> Entity {
     let mut palette = monster.kind.palette();
-    palette[1] = family.color();
+    palette[1] = if monster.modifiers.elite { modifiers::ELITE_COLOR } else { family.color() };
 
     let sprite = monster.kind.sprite();
     let body_size = collision::body_size(sprite);
-    commands
-        .spawn(MainBundle::from_xyz(x, y, z_pos::ENEMIES))
+    // Column of tiles facing the ship, see [Monster::blocks]
+    let front = monster.side.on_left_right(sprite.iter().map(|tile| tile.0).max().unwrap_or(0), 0);
+    let mut entity = commands.spawn_empty();
+    behaviors::attach(&mut entity, monster.kind);
+    if monster.modifiers.front_shield { entity.insert(BlocksLasers); }
+    if monster.modifiers.regeneration > 0 { entity.insert(Regeneration::default()); }
+    entity
+        .insert(MainBundle::from_xyz(x, y, z_pos::ENEMIES))
         .insert(SolidBody {
             body_type: BodyType::Enemy,
             width: body_size.x,
             height: body_size.y,
             bottom_right_anchor: false,
         })
+        .insert(Damage(1))
         .insert(SurvivalUI)
         .insert(monster.clone())
         .insert(MonsterLastMoved::default())
         .with_children(|builder| {
             for &(x, y, i, bg, fg, flip, rotation) in sprite {
+                // Modifiers show behind the glyphs, empty tiles are left untouched to keep them without hitbox
+                let mut bg = palette[bg];
+                if i != 0 && bg == Palette::Transparent {
+                    if monster.modifiers.front_shield && x == front { bg = modifiers::SHIELD_COLOR; }
+                    else if monster.modifiers.armor > 0 { bg = modifiers::ARMOR_COLOR; }
+                }
                 builder.spawn(
                     util::sprite(
                         i, x, y, 0.,
-                        palette[bg], palette[fg],
+                        bg, palette[fg],
                         flip, rotation,
                         atlas.clone(),
                     )
@@ -190,60 +273,201 @@ impl MonsterPath {
 pub struct Monster {
     pub kind: Monsters,
     pub lives: i16,
+    pub max_lives: i16,
     pub path: MonsterPath,
     pub init_pos: Vec2,
     pub side: Side,
+    pub modifiers: Modifiers,
+    /// Displacement added to the path, by behaviors such as [Dodges]
+    pub offset: Vec2,
 }
 
 impl Monster {
     pub fn new(kind: Monsters, path: MonsterPath, x: f32, y: f32, side: Side) -> Self {
-        Self { kind, lives: kind.hp(), path, init_pos: vec2(x, y), side }
+        Self { kind, lives: kind.hp(), max_lives: kind.hp(), path, init_pos: vec2(x, y), side, modifiers: Modifiers::default(), offset: Vec2::ZERO }
+    }
+
+    pub fn with_modifiers(self, modifiers: Modifiers) -> Self {
+        let max_lives = if modifiers.elite { self.kind.hp() * modifiers::ELITE_HP_MULTIPLIER } else { self.kind.hp() };
+        Self { lives: max_lives, max_lives, modifiers, ..self }
+    }
+
+    /// Weighted drop table, elites drop like bosses and [Minion]s (even boss kinds) drop little.
+    pub fn drops(&self, minion: bool) -> &'static [(Option<Pickups>, u32)] {
+        if self.modifiers.elite { BOSS_DROPS } else if minion { MINION_DROPS } else { self.kind.drops() }
+    }
+
+    /// Whether the front shield stops a shot travelling along [direction] (-1 or 1 on the x axis).
+    pub fn blocks(&self, direction: f32) -> bool {
+        // Monsters face the direction they come from
+        self.modifiers.front_shield && direction * self.side.to_sign_f32() < 0.
+    }
+
+    /// Returns the damage actually taken from a shot of [damage] travelling along [direction] (-1 or 1 on the x axis).
+    pub fn damage_taken(&self, damage: i16, direction: f32) -> i16 {
+        if self.blocks(direction) { return 0; }
+        (damage - self.modifiers.armor).max(1)
     }
 
     pub fn compute_translation(&self, t: f32) -> Vec3 {
-        let Vec2 { x, y } = self.path.compute_move(self.init_pos, t, self.side);
+        let Vec2 { x, y } = self.path.compute_move(self.init_pos, t, self.side) + self.offset;
         Vec3 { x, y, z: z_pos::ENEMIES }
     }
 }
 
+/// Boss explosion chain, before the boss breaks apart.
+#[derive(Component, Default)]
+pub struct Dying {
+    frame: usize,
+}
+
+/// Breaks the monster tiles apart and despawns it.
+fn break_apart(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    monster: &Monster,
+    pos: &Transform,
+    id: Entity,
+    children_query: &Query<&Children>,
+    tiles: &Query<(&TextModeTextureAtlasSprite, &Transform), Without<Monster>>,
+) {
+    let center = pos.translation.truncate() + collision::body_size(monster.kind.sprite()) / 2.;
+    debris::break_apart(
+        commands, atlas, center,
+        children_query
+            .iter_descendants(id)
+            .filter_map(|child| tiles.get(child).ok())
+            .map(|(sprite, tile_pos)| (sprite, pos.translation + tile_pos.translation)),
+    );
+    commands.entity(id).despawn_recursive();
+}
+
 pub fn monster_dies(
-    monsters: Query<(&Monster, &Invincible, Entity), Changed<Invincible>>,
+    monsters: Query<(&Monster, &Invincible, &Transform, Option<&Minion>, Option<&Splits>, Entity), Changed<Invincible>>,
+    children_query: Query<&Children>,
+    tiles: Query<(&TextModeTextureAtlasSprite, &Transform), Without<Monster>>,
+    textures: Res<Textures>,
     mut commands: Commands,
 ) {
-    for (monster, invincible, id) in monsters.iter() {
+    for (monster, invincible, pos, minion, splits, id) in monsters.iter() {
         if monster.lives <= 0 && invincible.0 == 0 {
-            commands.entity(id).despawn_recursive();
-
-            if monster.kind.is_boss() {
+            // Summoned bosses die like regular monsters
+            if monster.kind.is_boss() && minion.is_none() {
                 commands.insert_resource(CurrentRound::new());
+                commands.entity(id).remove::<SolidBody>().insert(Dying::default());
+            } else {
+                let center = pos.translation.truncate() + collision::body_size(monster.kind.sprite()) / 2.;
+                drop_pickup(&mut commands, &textures.mrmotext, monster, minion.is_some(), center);
+                if let Some(splits) = splits { split(&mut commands, &textures.mrmotext, monster, pos, splits); }
+                break_apart(&mut commands, &textures.mrmotext, monster, pos, id, &children_query, &tiles);
             }
         }
     }
 }
 
+pub fn explode_bosses(
+    mut bosses: Query<(&mut Dying, &Monster, &Transform, Option<&Splits>, Entity)>,
+    mut unlocks: ResMut<Unlocks>,
+    children_query: Query<&Children>,
+    tiles: Query<(&TextModeTextureAtlasSprite, &Transform), Without<Monster>>,
+    textures: Res<Textures>,
+    mut camera_effects: ResMut<CameraEffects>,
+    mut commands: Commands,
+) {
+    let mut rng = rand::thread_rng();
+    for (mut dying, monster, pos, splits, id) in bosses.iter_mut() {
+        dying.frame += 1;
+        if dying.frame % effects::BOSS_EXPLOSION_INTERVAL != 0 { continue; }
+
+        if dying.frame < effects::BOSS_EXPLOSION_INTERVAL * effects::BOSS_EXPLOSIONS {
+            let size = collision::body_size(monster.kind.sprite());
+            let point = pos.translation.truncate() + size * vec2(rng.gen(), rng.gen());
+            debris::explode(&mut commands, &textures.mrmotext, point);
+            camera_effects.shake(camera::BOSS_EXPLOSION_TRAUMA);
+        } else {
+            camera_effects.shake(camera::BOSS_DEATH_TRAUMA);
+            camera_effects.punch(camera::BOSS_DEATH_ZOOM);
+            camera_effects.flash(effects::FLASH_COLOR, camera::BOSS_DEATH_FLASH);
+            let center = pos.translation.truncate() + collision::body_size(monster.kind.sprite()) / 2.;
+            drop_pickup(&mut commands, &textures.mrmotext, monster, false, center);
+            unlocks.unlock_next_ship();
+            if let Some(splits) = splits { split(&mut commands, &textures.mrmotext, monster, pos, splits); }
+            break_apart(&mut commands, &textures.mrmotext, monster, pos, id, &children_query, &tiles);
+        }
+    }
+}
+
+/// Path time since the monster last changed path, in frames unless its movement is altered by [Bursts].
 #[derive(Component, Default)]
 pub struct MonsterLastMoved {
-    ago: usize,
+    ago: f32,
 }
 
 pub fn move_monsters(
-    mut monsters: Query<(&mut Transform, &mut MonsterLastMoved, &mut Monster, Option<&Invincible>)>,
+    mut commands: Commands,
+    mut monsters: Query<(&mut Transform, &mut MonsterLastMoved, &mut Monster, Option<&Invincible>, Option<&Bursts>, Option<&Minion>, Entity)>,
+    camera_effects: Res<CameraEffects>,
 ) {
-    for (mut monster_pos, mut monster_last_moved, mut monster, invincible) in monsters.iter_mut() {
+    if camera_effects.skip_frame() { return; }
+    for (mut monster_pos, mut monster_last_moved, mut monster, invincible, bursts, minion, id) in monsters.iter_mut() {
         if (invincible.is_some()
             && invincible.unwrap().0 > util::fight::ENEMY_COOLDOWN - util::fight::MONSTERS_FREEZE)
             || monster.lives <= 0
         { continue; }
 
-        if monster.path.is_linear() &&
+        // Minions fly through instead of settling in front of the ship
+        if minion.is_none() && monster.path.is_linear() &&
             ((monster.side == Side::Left && monster_pos.translation.x > tile_to_f32(4))
                 || (monster.side == Side::Right && monster_pos.translation.x < tile_to_f32(WIDTH - 8))) {
             monster.path = MovementTypes::Boss.to_path(monster.side);
-            monster.init_pos = vec2(monster_pos.translation.x, monster_pos.translation.y);
-            monster_last_moved.ago = 0;
+            monster.init_pos = vec2(monster_pos.translation.x, monster_pos.translation.y) - monster.offset;
+            monster_last_moved.ago = 0.;
         }
 
-        monster_pos.translation = monster.compute_translation(monster_last_moved.ago as f32);
-        monster_last_moved.ago += 1;
+        monster_pos.translation = monster.compute_translation(monster_last_moved.ago);
+        monster_last_moved.ago += bursts.map_or(1., |bursts| bursts.time_step());
+
+        // Minions are spawned on screen, so they are gone once they leave it
+        if minion.is_some() && is_oob(&monster_pos) { commands.entity(id).despawn_recursive(); }
     }
 }
+
+pub fn regenerate_monsters(
+    mut monsters: Query<(&mut Monster, &mut Regeneration)>,
+) {
+    for (mut monster, mut regeneration) in monsters.iter_mut() {
+        if monster.lives <= 0 || monster.lives >= monster.max_lives {
+            regeneration.0 = 0;
+            continue;
+        }
+        regeneration.0 += 1;
+        if regeneration.0 >= monster.modifiers.regeneration {
+            regeneration.0 = 0;
+            monster.lives += 1;
+        }
+    }
+}
+
+#[test]
+fn monster_modifiers() {
+    let monster = |modifiers| Monster::new(Monsters::Bat, MonsterPath::Static, 0., 0., Side::Left).with_modifiers(modifiers);
+    let plain = monster(Modifiers::default());
+
+    // Elites have more lives and drop like bosses
+    let elite = monster(Modifiers { elite: true, ..default() });
+    assert_eq!(elite.max_lives, plain.max_lives * modifiers::ELITE_HP_MULTIPLIER);
+    assert_eq!(elite.lives, elite.max_lives);
+    assert_eq!(elite.drops(false), Monsters::random_boss().drops());
+    assert_eq!(plain.drops(false), Monsters::Bat.drops());
+
+    // Armor reduces damage down to 1
+    let armored = monster(Modifiers { armor: 1, ..default() });
+    assert_eq!(armored.damage_taken(3, -1.), 2);
+    assert_eq!(armored.damage_taken(1, -1.), 1);
+
+    // Shields only block shots hitting the side facing the ship
+    let shielded = monster(Modifiers { front_shield: true, ..default() });
+    assert_eq!(shielded.damage_taken(2, -1.), 0);
+    assert_eq!(shielded.damage_taken(2, 1.), 2);
+    assert_eq!(plain.damage_taken(2, -1.), 2);
+}
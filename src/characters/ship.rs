@@ -1,21 +1,121 @@
+use bevy::math::vec2;
 use bevy::prelude::*;
 use bevy_text_mode::TextModeTextureAtlasSprite;
+use strum_macros::EnumIter;
 
 use crate::{collision, MainBundle, util};
 use crate::characters::monsters::Monster;
 use crate::collision::{BodyType, Contact, Invincible, SolidBody};
 use crate::graphics::animation::NoAnimation;
+use crate::graphics::camera::CameraEffects;
+use crate::graphics::debris;
+use crate::graphics::particles::{ParticleEmitter, Particles};
 use crate::graphics::sprites;
+use crate::graphics::sprites::TILE;
 use crate::graphics::text::glyph_index;
 use crate::progress::Progress;
+use crate::screens::continues::{Continued, GameOver};
 use crate::screens::survival::{Life, SurvivalUI};
-use crate::util::{ship, Side, size, z_pos};
+use crate::screens::Textures;
+use crate::util::{camera, Palette, particles, ship, Side, size, z_pos};
 use crate::util::size::tile_to_f32;
-use crate::weapons::WeaponChanged;
+use crate::weapons::{ActiveWeapon, WeaponChanged, Weapons};
 
-#[derive(Component)]
+#[derive(Debug, EnumIter, Copy, Clone, PartialEq, Eq)]
+pub enum Ships {
+    Rtemo,
+    Scout,
+    Tank,
+}
+
+/// Passive bonus of a ship.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Passive {
+    /// Starts with an extra bomb
+    Scavenger,
+    /// Dash cooldown is halved
+    Afterburner,
+    /// Shield regenerates twice as fast
+    Bulwark,
+}
+
+impl Passive {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Passive::Scavenger => "+1 bomb",
+            Passive::Afterburner => "quick dash",
+            Passive::Bulwark => "fast shield",
+        }
+    }
+}
+
+impl Ships {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ships::Rtemo => "rtemo",
+            Ships::Scout => "scout",
+            Ships::Tank => "tank",
+        }
+    }
+
+    pub fn sprite(&self) -> &[TILE] {
+        match self {
+            Ships::Rtemo => &sprites::SHIP,
+            Ships::Scout => &sprites::SHIP_SCOUT,
+            Ships::Tank => &sprites::SHIP_TANK,
+        }
+    }
+
+    /// Starting weapons (left, right)
+    pub fn weapons(&self) -> (Weapons, Weapons) {
+        match self {
+            Ships::Rtemo => (Weapons::Laser, Weapons::Finger),
+            Ships::Scout => (Weapons::Finger, Weapons::Shotgun),
+            Ships::Tank => (Weapons::DoubleCannon, Weapons::Spear),
+        }
+    }
+
+    /// Max speed, in pixels per frame
+    pub fn speed(&self) -> f32 {
+        match self {
+            Ships::Rtemo => ship::SPEED,
+            Ships::Scout => ship::SPEED * 1.5,
+            Ships::Tank => ship::SPEED * 0.75,
+        }
+    }
+
+    pub fn max_hp(&self) -> u8 {
+        match self {
+            Ships::Rtemo => 3,
+            Ships::Scout => 2,
+            Ships::Tank => 5,
+        }
+    }
+
+    pub fn passive(&self) -> Passive {
+        match self {
+            Ships::Rtemo => Passive::Scavenger,
+            Ships::Scout => Passive::Afterburner,
+            Ships::Tank => Passive::Bulwark,
+        }
+    }
+
+    /// Frames before the dash is available again
+    pub fn dash_cooldown(&self) -> usize {
+        if self.passive() == Passive::Afterburner { ship::DASH_COOLDOWN / 2 } else { ship::DASH_COOLDOWN }
+    }
+
+    /// Frames for the shield to come back after absorbing a hit
+    pub fn shield_regen(&self) -> usize {
+        if self.passive() == Passive::Bulwark { ship::SHIELD_REGEN / 2 } else { ship::SHIELD_REGEN }
+    }
+}
+
+/// [y] is the offset from [ship::INIT_Y] in pixels, [velocity] in pixels per frame.
+#[derive(Component, Default)]
 pub struct Ship {
-    y: i64,
+    y: f32,
+    velocity: f32,
 }
 
 #[derive(Default)]
@@ -24,28 +124,74 @@ pub struct ShipMoveEvent(pub i64);
 #[derive(Component)]
 pub struct ShipChar(pub Side);
 
+/// The ship takes no damage for the given number of frames.
+#[derive(Component)]
+pub struct Shielded(pub usize);
+
+/// Quick move along the rail with i-frames, available when [cooldown] is 0.
+#[derive(Component, Default)]
+pub struct Dash {
+    pub cooldown: usize,
+    remaining: usize,
+    direction: f32,
+}
+
+/// Absorbs one hit, then regenerates during [cooldown] frames.
+#[derive(Component)]
+pub struct ShipShield {
+    pub charged: bool,
+    pub cooldown: usize,
+}
+
+/// Ship border tile, colored while the [ShipShield] is charged or during a [Dash].
+#[derive(Component)]
+pub struct ShipBorder(Palette);
+
+/// The ship was destroyed [frame] frames ago, it is hidden and has no body.
+#[derive(Component, Default)]
+pub struct Wrecked {
+    frame: usize,
+}
+
+/// The ship slides back onto the rails after being destroyed, it gets its body back once in place.
+#[derive(Component)]
+pub struct Respawning;
+
+fn ship_body(kind: Ships) -> SolidBody {
+    let body_size = collision::body_size(kind.sprite());
+    SolidBody {
+        body_type: BodyType::Ship,
+        width: body_size.x,
+        height: body_size.y,
+        bottom_right_anchor: false,
+    }
+}
+
 pub fn spawn_ship(
     commands: &mut Commands,
     atlas: &Handle<TextureAtlas>,
+    kind: Ships,
 ) {
-    let body_size = collision::body_size(&sprites::SHIP);
+    let body_size = collision::body_size(kind.sprite());
     let colors = sprites::RTEMO_PALETTE;
     commands
-        .spawn(Ship { y: 0 })
+        .spawn(Ship::default())
         .insert(SurvivalUI)
         .insert(MainBundle::from_xyz(
             tile_to_f32(size::WIDTH) / 2. - tile_to_f32(2),
             ship::INIT_Y,
             z_pos::MACHINE,
         ))
-        .insert(SolidBody {
-            body_type: BodyType::Ship,
-            width: body_size.x,
-            height: body_size.y,
-            bottom_right_anchor: false,
-        })
+        .insert(ship_body(kind))
+        .insert(Dash::default())
+        .insert(ShipShield { charged: true, cooldown: 0 })
+        .insert(ParticleEmitter::new(
+            Particles::EngineTrail.particle(),
+            particles::ENGINE_TRAIL_INTERVAL,
+            Vec2::new(body_size.x / 2. - 4., -tile_to_f32(1)),
+        ))
         .with_children(|builder| {
-            for (x, y, i, bg, fg, flip, rotation) in sprites::SHIP {
+            for &(x, y, i, bg, fg, flip, rotation) in kind.sprite() {
                 let mut commands = builder
                     .spawn(util::sprite(
                         i, x, y, 0.,
@@ -55,36 +201,74 @@ pub fn spawn_ship(
                     ));
                 commands.insert(NoAnimation);
                 if (x == 1 || x == 2) && y == 2 { commands.insert(ShipChar(if x == 1 { Side::Left } else { Side::Right })); }
+                if x == 0 || x == 3 { commands.insert(ShipBorder(colors[fg])); }
             }
         });
 }
 
+/// Returns the vertical input, from -1 to 1 (keyboard or analog stick).
+fn vertical_input(
+    keys: &Input<KeyCode>,
+    gamepads: &Gamepads,
+    axes: &Axis<GamepadAxis>,
+) -> f32 {
+    let mut input = 0.;
+    if keys.pressed(KeyCode::Up) { input += 1. }
+    if keys.pressed(KeyCode::Down) { input -= 1. }
+    for gamepad in gamepads.iter() {
+        let value = axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY)).unwrap_or(0.);
+        if value.abs() > ship::DEAD_ZONE { input += value; }
+    }
+    input.clamp(-1., 1.)
+}
+
 pub fn update_ship_y(
+    mut commands: Commands,
     keys: Res<Input<KeyCode>>,
-    mut ship: Query<&mut Ship>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    progress: Res<Progress>,
+    mut ship: Query<(&mut Ship, &mut Dash, Option<&Invincible>, Entity), (Without<Wrecked>, Without<Respawning>)>,
 ) {
-    let moved = {
-        let mut moved = 0;
-        if keys.pressed(KeyCode::Up) { moved += 1 }
-        if keys.pressed(KeyCode::Down) { moved -= 1 }
-        moved
+    let Ok((mut ship, mut dash, invincible, id)) = ship.get_single_mut() else { return; };
+    let input = vertical_input(&keys, &gamepads, &axes);
+
+    // Start a dash in the direction held
+    if keys.just_pressed(ship::DASH_KEY) && dash.cooldown == 0 && dash.remaining == 0 && input != 0. {
+        dash.direction = input.signum();
+        dash.remaining = ship::DASH_FRAMES;
+        dash.cooldown = progress.ship.dash_cooldown();
+        commands.entity(id).insert(Invincible::at_least(invincible, ship::DASH_FRAMES));
+    }
+
+    let velocity = if dash.remaining > 0 {
+        dash.remaining -= 1;
+        dash.direction * ship::DASH_SPEED
+    } else if input != 0. {
+        // Accelerate toward the speed matching the input magnitude
+        let target = input * progress.ship_speed;
+        ship.velocity + (target - ship.velocity).clamp(-progress.ship_acceleration, progress.ship_acceleration)
+    } else {
+        ship.velocity * (1. - progress.ship_friction)
     };
+    let velocity = if velocity.abs() < 0.01 { 0. } else { velocity };
 
-    if moved != 0 {
-        let mut ship = ship.single_mut();
-        let mut new_y = moved + ship.y;
-        if new_y < ship::MIN_Y { new_y = ship::MIN_Y };
-        if new_y > ship::MAX_Y { new_y = ship::MAX_Y };
-        ship.y = new_y;
+    if velocity != 0. || ship.velocity != 0. {
+        ship.velocity = velocity;
+        ship.y += velocity;
+        if ship.y <= ship::MIN_Y || ship.y >= ship::MAX_Y {
+            ship.y = ship.y.clamp(ship::MIN_Y, ship::MAX_Y);
+            ship.velocity = 0.;
+        }
     }
 }
 
 pub fn update_ship_image(
     mut query: Query<(&mut Transform, &Ship), Changed<Ship>>,
-    progress: Res<Progress>,
 ) {
     if let Ok((mut transform, ship)) = query.get_single_mut() {
-        transform.translation.y = ship::INIT_Y + progress.ship_speed * ship.y as f32;
+        // Sub-pixel position, snapped to the pixel grid
+        transform.translation.y = (ship::INIT_Y + ship.y).round();
     }
 }
 
@@ -99,24 +283,156 @@ pub fn update_ship_name(
     }
 }
 
+pub fn update_shield(
+    mut commands: Commands,
+    mut ship: Query<(&mut Shielded, Entity)>,
+) {
+    for (mut shield, id) in ship.iter_mut() {
+        if shield.0 == 0 { commands.entity(id).remove::<Shielded>(); }
+        else { shield.0 -= 1; }
+    }
+}
+
+pub fn update_abilities(
+    mut ship: Query<(&mut Dash, &mut ShipShield)>,
+    mut borders: Query<(&mut TextModeTextureAtlasSprite, &ShipBorder)>,
+) {
+    let Ok((mut dash, mut shield)) = ship.get_single_mut() else { return; };
+    if dash.cooldown > 0 { dash.cooldown -= 1; }
+    if !shield.charged {
+        shield.cooldown = shield.cooldown.saturating_sub(1);
+        if shield.cooldown == 0 { shield.charged = true; }
+    }
+
+    for (mut sprite, &ShipBorder(color)) in borders.iter_mut() {
+        let fg: Color = if dash.remaining > 0 { ship::DASH_COLOR.into() }
+            else if shield.charged { ship::SHIELD_COLOR.into() }
+            else { color.into() };
+        if sprite.fg != fg { sprite.fg = fg; }
+    }
+}
+
 pub fn monsters_kill(
     mut life: Query<&mut Life>,
     mut contacts: EventReader<Contact>,
     mut monsters: Query<&mut Monster, Without<Invincible>>,
+    mut shields: Query<(&mut ShipShield, Option<&Shielded>)>,
+    mut camera_effects: ResMut<CameraEffects>,
+    progress: Res<Progress>,
 ) {
-    for Contact((body1, id1), (body2, id2)) in contacts.iter() {
-        match ((body1, id1), (body2, id2)) {
-            ((BodyType::Enemy, id_enemy), (BodyType::Ship, id_ship)) |
-            ((BodyType::Ship, id_ship), (BodyType::Enemy, id_enemy))
-            => {
-                if let Ok(mut life) = life.get_single_mut() {
-                    life.0 -= 1;
-                }
-                if let Ok(mut monster) = monsters.get_mut(*id_enemy) {
-                    monster.lives = 0;
-                }
+    for contact in contacts.iter() {
+        let Some((enemy, ship)) = contact.between(BodyType::Enemy, BodyType::Ship)
+            .or_else(|| contact.between(BodyType::EnemyShot, BodyType::Ship)) else { continue };
+        let absorbed = match shields.get_mut(ship.entity) {
+            Ok((_, Some(_))) => true,
+            Ok((mut shield, None)) if shield.charged => {
+                shield.charged = false;
+                shield.cooldown = progress.ship.shield_regen();
+                true
+            }
+            _ => false,
+        };
+        if absorbed {
+            camera_effects.shake(camera::SHIP_HIT_TRAUMA / 2.);
+        } else {
+            camera_effects.shake(camera::SHIP_HIT_TRAUMA);
+            camera_effects.flash(Palette::Red, camera::SHIP_HIT_FLASH);
+            if let Ok(mut life) = life.get_single_mut() {
+                life.0 -= enemy.damage as i8;
             }
-            _ => {}
         }
+        if let Ok(mut monster) = monsters.get_mut(enemy.entity) {
+            monster.lives = 0;
+        }
+    }
+}
+
+/// Destroys the ship when it runs out of lives: its tiles break apart,
+/// the weapons tumble away and the action slows down for a moment.
+pub fn wreck_ship(
+    mut commands: Commands,
+    life: Query<&Life, Changed<Life>>,
+    ship: Query<(&Transform, &SolidBody, Entity), With<Ship>>,
+    weapons: Query<(&ActiveWeapon, &Transform, Entity)>,
+    children_query: Query<&Children>,
+    tiles: Query<(&TextModeTextureAtlasSprite, &Transform)>,
+    textures: Res<Textures>,
+    mut camera_effects: ResMut<CameraEffects>,
+) {
+    let Ok(&Life(lives)) = life.get_single() else { return; };
+    if lives > 0 { return; }
+    let Ok((pos, body, id)) = ship.get_single() else { return; };
+
+    debris::break_apart(
+        &mut commands, &textures.mrmotext, body.center(pos.translation),
+        children_query
+            .iter_descendants(id)
+            .filter_map(|child| tiles.get(child).ok())
+            .map(|(sprite, tile_pos)| (sprite, pos.translation + tile_pos.translation)),
+    );
+    for (weapon, weapon_pos, weapon_id) in weapons.iter() {
+        let velocity = vec2(-weapon.side.to_sign_f32() * ship::WEAPON_TUMBLE_SPEED.x, ship::WEAPON_TUMBLE_SPEED.y);
+        debris::tumble(
+            &mut commands, &textures.mrmotext, velocity, ship::WEAPON_TUMBLE_INTERVAL,
+            children_query
+                .iter_descendants(weapon_id)
+                .filter_map(|child| tiles.get(child).ok())
+                .map(|(sprite, tile_pos)| (sprite, weapon_pos.translation + tile_pos.translation)),
+        );
+    }
+
+    commands.entity(id)
+        .remove::<SolidBody>()
+        .remove::<Invincible>()
+        .remove::<Shielded>()
+        .insert(Wrecked::default())
+        .insert(Visibility::Hidden);
+    camera_effects.shake(camera::SHIP_DEATH_TRAUMA);
+    camera_effects.flash(Palette::Red, camera::SHIP_DEATH_FLASH);
+    camera_effects.slow_motion(camera::SHIP_DEATH_SLOW_MOTION);
+}
+
+/// Sends [GameOver] [ship::WRECK_FRAMES] after the ship destruction.
+pub fn update_wreck(
+    mut ship: Query<&mut Wrecked>,
+    mut game_over: EventWriter<GameOver>,
+) {
+    let Ok(mut wrecked) = ship.get_single_mut() else { return; };
+    wrecked.frame += 1;
+    if wrecked.frame == ship::WRECK_FRAMES { game_over.send(GameOver); }
+}
+
+/// Brings the wrecked ship back below the screen after a continue, with full life.
+pub fn respawn_ship(
+    mut commands: Commands,
+    mut continued: EventReader<Continued>,
+    progress: Res<Progress>,
+    mut ship: Query<(&mut Ship, &mut Dash, &mut ShipShield, &mut Visibility, Entity), With<Wrecked>>,
+    mut life: Query<&mut Life>,
+) {
+    if continued.iter().next().is_none() { return; }
+    let Ok((mut ship, mut dash, mut shield, mut visibility, id)) = ship.get_single_mut() else { return; };
+
+    *ship = Ship { y: ship::RESPAWN_Y, velocity: 0. };
+    *dash = Dash::default();
+    *shield = ShipShield { charged: true, cooldown: 0 };
+    *visibility = Visibility::Inherited;
+    if let Ok(mut life) = life.get_single_mut() { life.0 = progress.max_hp as i8; }
+    commands.entity(id).remove::<Wrecked>().insert(Respawning);
+}
+
+/// Slides the respawning ship back onto the rails, then makes it invincible for a while.
+pub fn update_respawn(
+    mut commands: Commands,
+    progress: Res<Progress>,
+    mut ship: Query<(&mut Ship, Entity), With<Respawning>>,
+) {
+    let Ok((mut ship, id)) = ship.get_single_mut() else { return; };
+    ship.y = (ship.y + ship::RESPAWN_SPEED).min(0.);
+    if ship.y >= 0. {
+        commands.entity(id)
+            .remove::<Respawning>()
+            .insert(ship_body(progress.ship))
+            .insert(Invincible(ship::RESPAWN_INVINCIBILITY));
     }
 }
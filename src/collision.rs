@@ -1,35 +1,49 @@
 use bevy::app::{App, Plugin};
 use bevy::hierarchy::HierarchyQueryExt;
-use bevy::math::{vec2, vec3};
+use bevy::math::vec2;
 use bevy::prelude::*;
-use bevy::sprite::collide_aabb;
 use bevy::utils::default;
 use bevy_text_mode::TextModeTextureAtlasSprite;
 use strum::IntoEnumIterator;
 
-use crate::characters::monsters::Monsters;
+use crate::characters::monsters::{Monster, Monsters};
 use crate::graphics::sprites;
 use crate::graphics::sprites::TILE;
 use crate::util::{fight, Palette, size};
-use crate::weapons::{Weapon, Weapons};
+use crate::weapons::{Shot, WeaponDefinitions};
 
 /// Handles collisions.
 ///
 /// In order to get a collision we need:
 /// - [SolidBody] on the parent with its size (translation + size / 2. = center)
 /// - [TextModeTextureAtlasSprite] on the children entities, and [Hitbox::for_tile] returning a hitbox
-/// A [Contact] event will be sent after the collision.
+/// A [Contact] event will be sent after the collision, during [CollisionSet::Detect].
+/// Systems applying damage should run in [CollisionSet::Damage], systems destroying entities in [CollisionSet::Resolve].
 /// To pause collisions momentarily, add an [Invincible] component with the desired cooldown.
+/// Fast moving bodies should have a [LastPosition] updated when they move, to be tested along their movement.
 pub struct CollisionPlugin;
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_event::<Contact>()
-            .add_systems((collide.before(add_invincible), add_invincible, update_invincible));
+            .configure_sets((CollisionSet::Detect, CollisionSet::Damage, CollisionSet::Resolve).chain())
+            .add_systems((collide.in_set(CollisionSet::Detect), add_invincible.in_set(CollisionSet::Damage), update_invincible));
     }
 }
 
+/// Contacts are processed in this order every frame, so that a contact is never handled by a destruction
+/// system before its damage has been applied.
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum CollisionSet {
+    /// [Contact] events are sent
+    Detect,
+    /// Lives are decremented
+    Damage,
+    /// Shots and dead entities are despawned
+    Resolve,
+}
+
 /// Takes entity into account for collision detection. Entity children should have a [Hitbox].
 /// [body_type] is used to perform collision detection against the right bodies.
 /// [width] and [height] describe a rectangle containing all the children [Hitbox]-es.
@@ -41,12 +55,25 @@ pub struct SolidBody {
     pub bottom_right_anchor: bool,
 }
 
+impl SolidBody {
+    /// Returns the bottom left corner of the body for the entity [translation].
+    pub fn bottom_left(&self, translation: Vec3) -> Vec2 {
+        vec2(translation.x - if self.bottom_right_anchor { self.width } else { 0. }, translation.y)
+    }
+
+    /// Returns the center of the body for the entity [translation].
+    pub fn center(&self, translation: Vec3) -> Vec2 {
+        self.bottom_left(translation) + vec2(self.width, self.height) / 2.
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum BodyType {
     Enemy,
     EnemyShot,
     Ship,
     ShipShot,
+    Pickup,
     Ghost,
 }
 
@@ -56,17 +83,70 @@ impl BodyType {
             (BodyType::Enemy, BodyType::ShipShot) | (BodyType::ShipShot, BodyType::Enemy) => true,
             (BodyType::Ship, BodyType::EnemyShot) | (BodyType::EnemyShot, BodyType::Ship) => true,
             (BodyType::Enemy, BodyType::Ship) | (BodyType::Ship, BodyType::Enemy) => true,
+            (BodyType::Pickup, BodyType::Ship) | (BodyType::Ship, BodyType::Pickup) => true,
             _ => false
         }
     }
+
+    /// Whether a contact between the two bodies deals damage, invincible bodies only ignore those.
+    fn hurts(&self, other: &BodyType) -> bool {
+        *self != BodyType::Pickup && *other != BodyType::Pickup
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ContactBody {
+    pub body_type: BodyType,
+    pub entity: Entity,
+    /// Child tile whose [Hitbox] touched the other body.
+    pub tile: Entity,
+    /// Damage dealt to the other body, from the [Damage] component.
+    pub damage: i16,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Contact {
+    pub first: ContactBody,
+    pub second: ContactBody,
+    /// World position of the hit (center of the intersection of both hitboxes).
+    pub point: Vec2,
 }
 
-pub struct Contact(pub (BodyType, Entity), pub (BodyType, Entity));
+impl Contact {
+    pub fn bodies(&self) -> [&ContactBody; 2] { [&self.first, &self.second] }
+
+    /// Returns the bodies ordered as ([a], [b]) if the contact is between [a] and [b].
+    pub fn between(&self, a: BodyType, b: BodyType) -> Option<(&ContactBody, &ContactBody)> {
+        if self.first.body_type == a && self.second.body_type == b { Some((&self.first, &self.second)) }
+        else if self.first.body_type == b && self.second.body_type == a { Some((&self.second, &self.first)) }
+        else { None }
+    }
+}
+
+/// Damage dealt by a [SolidBody] to the bodies it touches.
+#[derive(Component, Copy, Clone, Debug)]
+pub struct Damage(pub i16);
+
+/// The [SolidBody] only reports its earliest contact each tick (for instance non-piercing shots).
+#[derive(Component, Debug)]
+pub struct SingleContact;
+
+/// Position of a moving [SolidBody] at the previous tick.
+/// Collisions are tested along the whole movement instead of the end position only.
+#[derive(Component, Debug)]
+pub struct LastPosition(pub Vec3);
 
 /// Excludes the entity from collision detection.
 #[derive(Component, Debug)]
 pub struct Invincible(pub usize);
 
+impl Invincible {
+    /// Invincibility for [frames], or for longer if [current] lasts longer.
+    pub fn at_least(current: Option<&Invincible>, frames: usize) -> Self {
+        Self(current.map_or(frames, |current| current.0.max(frames)))
+    }
+}
+
 #[derive(Component, Default, Clone, Copy)]
 pub struct Hitbox {
     pub dx: f32,
@@ -95,6 +175,13 @@ impl Hitbox {
         }
     }
 
+    pub fn size(&self) -> Vec2 { vec2(self.width, self.height) }
+
+    /// Returns the world center of the hitbox of a tile at [tile] in the body at [body].
+    pub fn center(&self, body: &Transform, tile: &Transform) -> Vec2 {
+        (body.translation + tile.translation).truncate() + vec2(self.dx, self.dy) + self.size() / 2.
+    }
+
     // TODO: formula to update hitbox based on the tile [flip] and [rotation] properties.
     pub fn with_flip_and_rotation(&self, flip: bool, rotation: u8) -> Self {
         Hitbox {
@@ -116,13 +203,23 @@ pub fn add_invincible(
     mut commands: Commands,
     mut contact: EventReader<Contact>,
     invincible: Query<Option<&Invincible>>,
+    monsters: Query<&Monster>,
+    shots: Query<&Shot>,
 ) {
-    for Contact((b1, e1), (b2, e2)) in contact.iter() {
-        for (b, e) in [(b1, e1), (b2, e2)] {
-            if *b == BodyType::Ship || *b == BodyType::Enemy {
+    for contact in contact.iter() {
+        // Collecting a pickup doesn't hurt
+        if contact.between(BodyType::Pickup, BodyType::Ship).is_some() { continue; }
+        // Shots blocked by a front shield neither freeze nor protect the monster
+        if let Some((enemy, shot)) = contact.between(BodyType::Enemy, BodyType::ShipShot) {
+            if let (Ok(monster), Ok(shot)) = (monsters.get(enemy.entity), shots.get(shot.entity)) {
+                if monster.blocks(shot.direction()) { continue; }
+            }
+        }
+        for &ContactBody { body_type, entity, .. } in contact.bodies() {
+            if body_type == BodyType::Ship || body_type == BodyType::Enemy {
                 // TODO: Small freeze for enemies, global freeze if the ship is hit, long global freeze if ship is destroyed
-                if let Ok(Some(_)) = invincible.get(*e) { continue; }
-                if let Some(mut entity_commands) = commands.get_entity(*e) {
+                if let Ok(Some(_)) = invincible.get(entity) { continue; }
+                if let Some(mut entity_commands) = commands.get_entity(entity) {
                     entity_commands.insert(Invincible(fight::ENEMY_COOLDOWN));
                 }
                 // info!("Hit!");
@@ -144,26 +241,67 @@ pub fn update_invincible(
     }
 }
 
+/// Returns the position travelled by the entity since the last tick, if it has a [LastPosition].
+fn displacement(pos: &Transform, last: Option<&LastPosition>) -> Vec2 {
+    last.map_or(Vec2::ZERO, |last| (pos.translation - last.0).truncate())
+}
+
+/// Sweeps a point from [start] along [delta] against the rectangle of [center] and [half_size].
+/// Returns the fraction of [delta] travelled before entering the rectangle, or None if the segment misses it.
+pub fn sweep_aabb(start: Vec2, delta: Vec2, center: Vec2, half_size: Vec2) -> Option<f32> {
+    let (min, max) = (center - half_size, center + half_size);
+    let (mut t_enter, mut t_exit) = (0f32, 1f32);
+
+    for (start, delta, min, max) in [(start.x, delta.x, min.x, max.x), (start.y, delta.y, min.y, max.y)] {
+        if delta.abs() < f32::EPSILON {
+            // Not moving on this axis: the point must already be inside the slab
+            if start <= min || start >= max { return None; }
+        } else {
+            let (t1, t2) = ((min - start) / delta, (max - start) / delta);
+            t_enter = t_enter.max(t1.min(t2));
+            t_exit = t_exit.min(t1.max(t2));
+            if t_enter >= t_exit { return None; }
+        }
+    }
+
+    Some(t_enter)
+}
+
+/// Collides the rectangle of [center1] and [size1] moving by [delta] since the last tick
+/// with the static rectangle of [center2] and [size2].
+fn swept_collide(center1: Vec2, size1: Vec2, delta: Vec2, center2: Vec2, size2: Vec2) -> Option<f32> {
+    sweep_aabb(center1 - delta, delta, center2, (size1 + size2) / 2.)
+}
+
+/// Returns the center of the intersection of two rectangles.
+fn hit_point(center1: Vec2, size1: Vec2, center2: Vec2, size2: Vec2) -> Vec2 {
+    let min = (center1 - size1 / 2.).max(center2 - size2 / 2.);
+    let max = (center1 + size1 / 2.).min(center2 + size2 / 2.);
+    (min + max) / 2.
+}
+
 pub fn collide(
-    colliders: Query<(&SolidBody, &Transform, Entity), Without<Invincible>>,
+    colliders: Query<(&SolidBody, &Transform, Option<&LastPosition>, Option<&Damage>, Option<&Invincible>, Option<&SingleContact>, Entity)>,
     children_query: Query<&Children>,
     hitboxes: Query<(&TextModeTextureAtlasSprite, &Transform), Without<SolidBody>>,
     mut contact: EventWriter<Contact>,
 ) {
-    let bodies = &colliders.iter().collect::<Vec<(&SolidBody, &Transform, Entity)>>();
-    for (i, &(body1, pos1, id1)) in bodies.iter().enumerate() {
-        'for_body: for &(body2, pos2, id2) in bodies.iter().skip(i) {
+    let bodies = &colliders.iter().collect::<Vec<_>>();
+    // Contacts with the fraction of the movement travelled before the hit
+    let mut contacts: Vec<(f32, Contact)> = vec![];
+
+    for (i, &(body1, pos1, last1, damage1, invincible1, _, id1)) in bodies.iter().enumerate() {
+        'for_body: for &(body2, pos2, last2, damage2, invincible2, _, id2) in bodies.iter().skip(i) {
             if !body1.body_type.can_collide(&body2.body_type) { continue }
+            if (invincible1.is_some() || invincible2.is_some()) && body1.body_type.hurts(&body2.body_type) { continue }
+
+            // Movement of body 1 relative to body 2 since the last tick, so that fast bodies can't tunnel through
+            let delta = displacement(pos1, last1) - displacement(pos2, last2);
 
             // Collide outer bounds first to avoid complex computations
-            // collide 1/3 args must be the center of the rectangle, 2/4 args are the rectangle size
-            if collide_aabb::collide(
-                vec3(pos1.translation.x + if body1.bottom_right_anchor { -body1.width / 2. } else { body1.width / 2. },
-                     pos1.translation.y + body1.height / 2., 0.),
-                vec2(body1.width, body1.height),
-                vec3(pos2.translation.x + if body2.bottom_right_anchor { -body2.width / 2. } else { body2.width / 2. },
-                     pos2.translation.y + body2.height / 2., 0.),
-                vec2(body2.width, body2.height)
+            if swept_collide(
+                body1.center(pos1.translation), vec2(body1.width, body1.height), delta,
+                body2.center(pos2.translation), vec2(body2.width, body2.height),
             ).is_none() { continue }
 
             // Collide entity 1 children with entity 2 children
@@ -178,21 +316,30 @@ pub fn collide(
                     let Some(mut hitbox2) = Hitbox::for_tile(sprite2.index, sprite2.bg == transparent) else { continue };
                     hitbox2 = hitbox2.with_flip_and_rotation(sprite2.flip_x, sprite2.rotation);
 
-                    if collide_aabb::collide(
-                        vec3(pos1.translation.x + cpos1.translation.x + hitbox1.dx + hitbox1.width / 2.,
-                             pos1.translation.y + cpos1.translation.y + hitbox1.dy + hitbox1.height / 2., 0.),
-                        vec2(hitbox1.width, hitbox1.height),
-                        vec3(pos2.translation.x + cpos2.translation.x + hitbox2.dx + hitbox2.width / 2.,
-                             pos2.translation.y + cpos2.translation.y + hitbox2.dy + hitbox2.height / 2., 0.),
-                        vec2(hitbox2.width, hitbox2.height)
-                    ).is_some() {
-                        contact.send(Contact((body1.body_type, id1), (body2.body_type, id2)));
+                    let (center1, center2) = (hitbox1.center(pos1, cpos1), hitbox2.center(pos2, cpos2));
+                    if let Some(t) = swept_collide(center1, hitbox1.size(), delta, center2, hitbox2.size()) {
+                        contacts.push((t, Contact {
+                            first: ContactBody { body_type: body1.body_type, entity: id1, tile: child1, damage: damage1.map_or(0, |d| d.0) },
+                            second: ContactBody { body_type: body2.body_type, entity: id2, tile: child2, damage: damage2.map_or(0, |d| d.0) },
+                            point: hit_point(center1 - delta * (1. - t), hitbox1.size(), center2, hitbox2.size()),
+                        }));
                         break 'for_body;
                     }
                 }
             }
         }
     }
+
+    // Send contacts in hit order, bodies with [SingleContact] only report the first one
+    contacts.sort_by(|(t1, _), (t2, _)| t1.total_cmp(t2));
+    let mut consumed: Vec<Entity> = vec![];
+    for (_, c) in contacts {
+        if c.bodies().iter().any(|body| consumed.contains(&body.entity)) { continue }
+        for body in c.bodies() {
+            if let Ok((.., Some(_), _)) = colliders.get(body.entity) { consumed.push(body.entity); }
+        }
+        contact.send(c);
+    }
 }
 
 #[test]
@@ -210,8 +357,18 @@ fn sprites_have_hitbox() {
         assert!(has_hitbox(monster.sprite()), "The monster {:?} has no hitbox!", monster)
     }
 
-    for weapon in Weapons::iter() {
-        let w: Weapon = weapon.into();
-        assert!(Hitbox::for_tile(w.shot_tile.index, w.shot_tile.bg == Palette::Transparent).is_some(), "The weapon {:?} has no hitbox!", weapon)
-    }
-}
\ No newline at end of file
+    let weapons: WeaponDefinitions = ron::from_str(include_str!("../assets/weapons.weapons.ron")).expect("Couldn't parse weapon definitions.");
+    assert_eq!(weapons.validate(), Ok(()));
+}
+
+#[test]
+fn fast_shots_dont_tunnel() {
+    let thin_wall = (vec2(10., 0.), vec2(1., 8.));
+
+    // Moving 20px in one tick through a 1px wide wall
+    assert!(swept_collide(vec2(20., 0.), vec2(8., 1.), vec2(20., 0.), thin_wall.0, thin_wall.1).is_some());
+    // Passing above the wall
+    assert!(swept_collide(vec2(20., 10.), vec2(8., 1.), vec2(20., 0.), thin_wall.0, thin_wall.1).is_none());
+    // Static overlap
+    assert_eq!(swept_collide(vec2(10., 0.), vec2(8., 1.), Vec2::ZERO, thin_wall.0, thin_wall.1), Some(0.));
+}
@@ -0,0 +1,167 @@
+use bevy::hierarchy::HierarchyQueryExt;
+use bevy::math::vec2;
+use bevy::prelude::*;
+use bevy_text_mode::TextModeTextureAtlasSprite;
+
+use crate::collision::{BodyType, collide, Contact, Hitbox, SolidBody};
+use crate::graphics::text;
+use crate::graphics::text::color_text;
+use crate::screens::Textures;
+use crate::util::{debug, misc, Palette, size, sprite, z_pos};
+
+/// Collision debug overlay, toggled with [debug::TOGGLE_KEY].
+///
+/// - Every [SolidBody] outer rectangle and its children [Hitbox]-es are drawn in the color of their [BodyType]
+/// - Bodies which produced a [Contact] this frame are highlighted
+/// - The number of bodies per [BodyType] is displayed in the bottom frame, above the score
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(DebugOverlay(false))
+            .add_systems((toggle_overlay, draw_overlay.after(toggle_overlay).after(collide)));
+    }
+}
+
+#[derive(Resource)]
+pub struct DebugOverlay(pub bool);
+
+/// Rectangles are redrawn every frame.
+#[derive(Component)]
+struct DebugRect;
+
+#[derive(Component)]
+struct BodyCount;
+
+const BODY_TYPES: [BodyType; 6] = [BodyType::Enemy, BodyType::EnemyShot, BodyType::Ship, BodyType::ShipShot, BodyType::Pickup, BodyType::Ghost];
+
+fn body_color(body_type: BodyType) -> Palette {
+    match body_type {
+        BodyType::Enemy => Palette::Red,
+        BodyType::EnemyShot => Palette::Lava,
+        BodyType::Ship => Palette::Blue,
+        BodyType::ShipShot => Palette::LightCactus,
+        BodyType::Pickup => Palette::LightPurple,
+        BodyType::Ghost => Palette::Gravel,
+    }
+}
+
+fn body_label(body_type: BodyType) -> &'static str {
+    match body_type {
+        BodyType::Enemy => "e",
+        BodyType::EnemyShot => "es",
+        BodyType::Ship => "s",
+        BodyType::ShipShot => "ss",
+        BodyType::Pickup => "p",
+        BodyType::Ghost => "g",
+    }
+}
+
+fn toggle_overlay(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut overlay: ResMut<DebugOverlay>,
+    ui: Query<Entity, Or<(With<DebugRect>, With<BodyCount>)>>,
+) {
+    if !keys.just_pressed(debug::TOGGLE_KEY) { return; }
+
+    overlay.0 = !overlay.0;
+    if overlay.0 {
+        commands
+            .spawn(color_text("", 2, debug::BODY_COUNT_Y, z_pos::DEBUG, Palette::Black, Palette::LightGold))
+            .insert(BodyCount);
+    } else {
+        for e in &ui { commands.entity(e).despawn_recursive(); }
+    }
+}
+
+fn spawn_rect(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    origin: Vec2,
+    rect_size: Vec2,
+    color: Palette,
+    alpha: f32,
+) {
+    let mut bundle = sprite(
+        misc::BLOCK_TILE, 0, 0, z_pos::DEBUG,
+        Palette::Transparent, color,
+        false, 0,
+        atlas.clone(),
+    );
+    bundle.sprite.alpha = alpha;
+    bundle.transform.translation.x = origin.x;
+    bundle.transform.translation.y = origin.y;
+    bundle.transform.scale = Vec3::new(rect_size.x / size::tile_to_f32(1), rect_size.y / size::tile_to_f32(1), 1.);
+    commands.spawn(bundle).insert(DebugRect);
+}
+
+fn draw_overlay(
+    mut commands: Commands,
+    overlay: Res<DebugOverlay>,
+    mut contacts: EventReader<Contact>,
+    bodies: Query<(&SolidBody, &Transform, Entity)>,
+    children_query: Query<&Children>,
+    hitboxes: Query<(&TextModeTextureAtlasSprite, &Transform), Without<SolidBody>>,
+    rects: Query<Entity, With<DebugRect>>,
+    mut count: Query<&mut text::Text, With<BodyCount>>,
+    textures: Option<Res<Textures>>,
+) {
+    if !overlay.0 {
+        contacts.clear();
+        return;
+    }
+    let Some(textures) = textures else { return; };
+
+    for e in &rects { commands.entity(e).despawn(); }
+
+    let touched = contacts
+        .iter()
+        .flat_map(|contact| contact.bodies().map(|body| body.entity))
+        .collect::<Vec<Entity>>();
+    let transparent: Color = Palette::Transparent.into();
+    let mut counts = [0; BODY_TYPES.len()];
+
+    for (body, pos, id) in &bodies {
+        let color = body_color(body.body_type);
+        if let Some(i) = BODY_TYPES.iter().position(|b| *b == body.body_type) { counts[i] += 1; }
+
+        // Outer rectangle, filled if the body touched another one this frame
+        let origin = body.bottom_left(pos.translation);
+        if touched.contains(&id) {
+            spawn_rect(&mut commands, &textures.mrmotext, origin, vec2(body.width, body.height), Palette::LightGold, debug::CONTACT_ALPHA);
+        }
+        for (corner, rect_size) in [
+            (origin, vec2(body.width, 1.)),
+            (origin + vec2(0., body.height - 1.), vec2(body.width, 1.)),
+            (origin, vec2(1., body.height)),
+            (origin + vec2(body.width - 1., 0.), vec2(1., body.height)),
+        ] {
+            spawn_rect(&mut commands, &textures.mrmotext, corner, rect_size, color, debug::BODY_ALPHA);
+        }
+
+        // Children hitboxes
+        for child in children_query.iter_descendants(id) {
+            let Ok((sprite, cpos)) = hitboxes.get(child) else { continue };
+            let Some(hitbox) = Hitbox::for_tile(sprite.index, sprite.bg == transparent) else { continue };
+            let hitbox = hitbox.with_flip_and_rotation(sprite.flip_x, sprite.rotation);
+            spawn_rect(
+                &mut commands, &textures.mrmotext,
+                vec2(pos.translation.x + cpos.translation.x + hitbox.dx, pos.translation.y + cpos.translation.y + hitbox.dy),
+                vec2(hitbox.width, hitbox.height),
+                color, debug::HITBOX_ALPHA,
+            );
+        }
+    }
+
+    let counts = BODY_TYPES
+        .iter()
+        .zip(counts)
+        .map(|(body_type, n)| format!("{}:{}", body_label(*body_type), n))
+        .collect::<Vec<String>>()
+        .join(" ");
+    if let Ok(mut text) = count.get_single_mut() {
+        if text.text != counts { text.text = counts; }
+    }
+}
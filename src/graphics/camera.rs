@@ -0,0 +1,167 @@
+use bevy::math::vec2;
+use bevy::prelude::*;
+use bevy_text_mode::TextModeTextureAtlasSprite;
+use rand::Rng;
+
+use crate::GameState;
+use crate::screens::Textures;
+use crate::util::{camera, misc, Palette, size, sprite, z_pos};
+use crate::util::size::tile_to_f32;
+
+/// Handles the camera and its effects (shake, zoom punches, flashes, slow motion).
+///
+/// Effects are requested through the [CameraEffects] resource, their intensity is cycled with [camera::INTENSITY_KEY].
+/// The camera always moves by whole pixels and zooms by whole pixel sizes to preserve the text-mode look.
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(CameraEffects::default())
+            .add_startup_system(spawn_camera)
+            .add_systems((cycle_intensity, update_camera, update_flash))
+            .add_system(reset.in_schedule(OnExit(GameState::Survival)));
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Flash {
+    color: Palette,
+    frame: usize,
+    duration: usize,
+}
+
+#[derive(Resource)]
+pub struct CameraEffects {
+    /// Multiplier for every effect, 0 to disable them
+    pub intensity: f32,
+    trauma: f32,
+    zoom: f32,
+    flash: Option<Flash>,
+    slow_motion: usize,
+}
+
+impl Default for CameraEffects {
+    fn default() -> Self {
+        Self { intensity: 1., trauma: 0., zoom: 0., flash: None, slow_motion: 0 }
+    }
+}
+
+impl CameraEffects {
+    /// Adds [trauma] (0 to 1), the shake amplitude is proportional to trauma².
+    pub fn shake(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.);
+    }
+
+    /// Zooms in by [zoom] (relative to the normal scale), then zooms back out.
+    pub fn punch(&mut self, zoom: f32) {
+        self.zoom = self.zoom.max(zoom);
+    }
+
+    /// Fills the screen with [color] for [duration] frames, fading out.
+    pub fn flash(&mut self, color: Palette, duration: usize) {
+        self.flash = Some(Flash { color, frame: 0, duration });
+    }
+
+    /// Slows the action down during [duration] frames.
+    pub fn slow_motion(&mut self, duration: usize) {
+        self.slow_motion = self.slow_motion.max(duration);
+    }
+
+    /// Returns true if moving things should skip this frame because of slow motion.
+    pub fn skip_frame(&self) -> bool {
+        self.slow_motion > 0 && self.slow_motion % camera::SLOW_MOTION_RATE != 0
+    }
+}
+
+#[derive(Component)]
+struct FlashOverlay;
+
+fn cycle_intensity(
+    keys: Res<Input<KeyCode>>,
+    mut effects: ResMut<CameraEffects>,
+) {
+    if !keys.just_pressed(camera::INTENSITY_KEY) { return; }
+
+    let current = camera::INTENSITIES.iter().position(|&intensity| intensity == effects.intensity).unwrap_or(0);
+    effects.intensity = camera::INTENSITIES[(current + 1) % camera::INTENSITIES.len()];
+}
+
+fn camera_center() -> Vec3 {
+    Vec3::new(tile_to_f32(size::WIDTH) / 2., tile_to_f32(size::HEIGHT) / 2., 100.)
+}
+
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle {
+        transform: Transform {
+            scale: Vec3::new(1. / size::SCALE, 1. / size::SCALE, 1.),
+            translation: camera_center(),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+}
+
+fn update_camera(
+    mut effects: ResMut<CameraEffects>,
+    mut camera: Query<&mut Transform, With<Camera>>,
+) {
+    let Ok(mut transform) = camera.get_single_mut() else { return; };
+
+    // Shake by whole pixels
+    let shake = effects.trauma.powi(2) * camera::MAX_SHAKE * effects.intensity;
+    let mut rng = rand::thread_rng();
+    let offset = vec2(rng.gen_range(-1f32..=1.) * shake, rng.gen_range(-1f32..=1.) * shake).round();
+    transform.translation = camera_center() + offset.extend(0.);
+
+    // Zoom by whole pixel sizes
+    let pixel_size = (size::SCALE * (1. + effects.zoom * effects.intensity)).round();
+    transform.scale = Vec3::new(1. / pixel_size, 1. / pixel_size, 1.);
+
+    effects.trauma = (effects.trauma - camera::TRAUMA_DECAY).max(0.);
+    effects.zoom = (effects.zoom - camera::ZOOM_DECAY).max(0.);
+    effects.slow_motion = effects.slow_motion.saturating_sub(1);
+}
+
+fn update_flash(
+    mut commands: Commands,
+    mut effects: ResMut<CameraEffects>,
+    mut overlay: Query<(&mut TextModeTextureAtlasSprite, Entity), With<FlashOverlay>>,
+    textures: Option<Res<Textures>>,
+) {
+    let Some(textures) = textures else { return; };
+    let intensity = effects.intensity;
+
+    let Some(flash) = effects.flash.as_mut() else {
+        for (_, e) in &overlay { commands.entity(e).despawn_recursive(); }
+        return;
+    };
+
+    flash.frame += 1;
+    if flash.frame >= flash.duration || intensity <= 0. {
+        effects.flash = None;
+        return;
+    }
+
+    let alpha = camera::FLASH_ALPHA * intensity.min(1.) * (1. - flash.frame as f32 / flash.duration as f32);
+    if let Ok((mut sprite, _)) = overlay.get_single_mut() {
+        sprite.fg = flash.color.into();
+        sprite.alpha = alpha;
+    } else {
+        let mut bundle = sprite(
+            misc::BLOCK_TILE, 0, 0, z_pos::FLASH,
+            Palette::Transparent, flash.color,
+            false, 0,
+            textures.mrmotext.clone(),
+        );
+        bundle.sprite.alpha = alpha;
+        bundle.transform.scale = Vec3::new(size::WIDTH as f32, size::HEIGHT as f32, 1.);
+        commands.spawn(bundle).insert(FlashOverlay);
+    }
+}
+
+fn reset(
+    mut effects: ResMut<CameraEffects>,
+) {
+    *effects = CameraEffects { intensity: effects.intensity, ..default() };
+}
@@ -0,0 +1,83 @@
+use std::f32::consts::TAU;
+
+use bevy::math::vec2;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy_text_mode::TextModeTextureAtlasSprite;
+use rand::Rng;
+
+use crate::graphics::particles;
+use crate::graphics::particles::Particle;
+use crate::graphics::text::glyph_index;
+use crate::screens::survival::SurvivalUI;
+use crate::util::{effects, Palette, z_pos};
+
+/// Breaks [tiles] (sprite, world translation) apart, away from [center].
+pub fn break_apart<'a>(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    center: Vec2,
+    tiles: impl Iterator<Item=(&'a TextModeTextureAtlasSprite, Vec3)>,
+) {
+    let mut rng = rand::thread_rng();
+    for (sprite, translation) in tiles {
+        let direction = (translation.truncate() + vec2(4., 4.) - center).normalize_or_zero();
+        let velocity = direction * rng.gen_range(0.5..1.5) * effects::DEBRIS_SPEED
+            + vec2(rng.gen_range(-0.3..0.3), rng.gen_range(0.5..1.));
+        let id = particles::spawn_tile(commands, atlas, sprite, translation, Particle {
+            velocity,
+            gravity: effects::DEBRIS_GRAVITY,
+            lifetime: effects::DEBRIS_LIFETIME,
+            ..default()
+        });
+        commands.entity(id).insert(SurvivalUI);
+    }
+}
+
+/// Sends [tiles] (sprite, world translation) tumbling away with [velocity],
+/// turning by a quarter every [rotation_interval] frames.
+pub fn tumble<'a>(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    velocity: Vec2,
+    rotation_interval: usize,
+    tiles: impl Iterator<Item=(&'a TextModeTextureAtlasSprite, Vec3)>,
+) {
+    for (sprite, translation) in tiles {
+        let id = particles::spawn_tile(commands, atlas, sprite, translation, Particle {
+            velocity,
+            gravity: effects::DEBRIS_GRAVITY,
+            lifetime: effects::DEBRIS_LIFETIME * 2,
+            rotation_interval,
+            ..default()
+        });
+        commands.entity(id).insert(SurvivalUI);
+    }
+}
+
+/// Spawns a burst of explosion tiles at [point].
+pub fn explode(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    point: Vec2,
+) {
+    let mut rng = rand::thread_rng();
+    for i in 0..effects::EXPLOSION_TILES {
+        let angle = TAU * i as f32 / effects::EXPLOSION_TILES as f32 + rng.gen_range(-0.3..0.3);
+        let glyph = if i % 2 == 0 { '*' } else { '+' };
+        let sprite = TextModeTextureAtlasSprite {
+            index: glyph_index(glyph).unwrap_or(0),
+            fg: (if i % 2 == 0 { Palette::Lava } else { Palette::LightGold }).into(),
+            bg: Palette::Transparent.into(),
+            alpha: 1.,
+            anchor: Anchor::Center,
+            ..default()
+        };
+        let id = particles::spawn_tile(commands, atlas, &sprite, point.extend(z_pos::SHOTS), Particle {
+            velocity: vec2(angle.cos(), angle.sin()) * rng.gen_range(0.4..1.2),
+            lifetime: effects::EXPLOSION_LIFETIME,
+            ..default()
+        });
+        commands.entity(id).insert(SurvivalUI);
+    }
+}
@@ -4,6 +4,8 @@ use bevy_tweening::TweeningPlugin;
 
 use crate::graphics::animation::AnimationPlugin;
 use crate::graphics::background::BackgroundPlugin;
+use crate::graphics::camera::CameraPlugin;
+use crate::graphics::particles::ParticlesPlugin;
 use crate::graphics::text::TextPlugin;
 
 pub mod frame;
@@ -12,6 +14,9 @@ pub mod background;
 pub mod tiles;
 pub mod sprites;
 pub mod transition;
+pub mod debris;
+pub mod particles;
+pub mod camera;
 mod background_sprites;
 pub(crate) mod animation;
 
@@ -25,6 +30,8 @@ impl Plugin for GraphicsPlugin {
             .add_plugin(TextPlugin)
             .add_plugin(BackgroundPlugin)
             .add_plugin(TweeningPlugin)
-            .add_plugin(AnimationPlugin);
+            .add_plugin(AnimationPlugin)
+            .add_plugin(ParticlesPlugin)
+            .add_plugin(CameraPlugin);
     }
 }
\ No newline at end of file
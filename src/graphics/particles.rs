@@ -0,0 +1,271 @@
+use bevy::math::vec2;
+use bevy::prelude::*;
+use bevy_text_mode::{TextModeSpriteSheetBundle, TextModeTextureAtlasSprite};
+use rand::Rng;
+
+use crate::collision::{CollisionSet, Contact};
+use crate::graphics::animation::NoAnimation;
+use crate::graphics::camera::CameraEffects;
+use crate::graphics::text::glyph_index;
+use crate::screens::Textures;
+use crate::util::{effects, Palette, particles, Side, sprite, z_pos};
+
+/// Handles short-lived glyph particles.
+///
+/// - [burst] spawns particles once (muzzle flashes, sparks…)
+/// - [spawn_tile] turns an existing tile into a particle (debris, see [crate::graphics::debris])
+/// - [ParticleEmitter] spawns particles periodically at the entity position (engine trails…)
+/// - A few particles are emitted on every [Contact]
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems((emit_particles, update_particles, hit_sparks.after(CollisionSet::Detect)));
+    }
+}
+
+/// Particle description (left orientation).
+#[derive(Copy, Clone)]
+pub struct Particle {
+    pub index: usize,
+    pub bg: Palette,
+    pub fg: Palette,
+    pub velocity: Vec2,
+    /// Random velocity in [-spread, spread] added to [velocity]
+    pub spread: Vec2,
+    pub gravity: f32,
+    pub lifetime: usize,
+    /// Frames between two rotation steps, 0 to keep the initial rotation
+    pub rotation_interval: usize,
+}
+
+impl Default for Particle {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            bg: Palette::Transparent,
+            fg: Palette::LightGold,
+            velocity: Vec2::ZERO,
+            spread: Vec2::ZERO,
+            gravity: 0.,
+            lifetime: 20,
+            rotation_interval: 0,
+        }
+    }
+}
+
+impl Particle {
+    pub fn with_side(self, side: Side) -> Self {
+        Self { velocity: vec2(self.velocity.x * side.to_sign_f32(), self.velocity.y), ..self }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum Particles {
+    MuzzleFlash,
+    LaserSpark,
+    HitSpark,
+    EngineTrail,
+}
+
+impl Particles {
+    pub fn particle(&self) -> Particle {
+        match self {
+            Particles::MuzzleFlash => Particle {
+                index: glyph_index('+').unwrap(),
+                fg: Palette::LightGold,
+                velocity: vec2(-0.4, 0.),
+                spread: vec2(0.1, 0.3),
+                lifetime: 8,
+                rotation_interval: 2,
+                ..default()
+            },
+            Particles::LaserSpark => Particle {
+                index: glyph_index('\'').unwrap(),
+                fg: Palette::LightRed,
+                velocity: vec2(0., 0.3),
+                spread: vec2(0.3, 0.3),
+                gravity: 0.02,
+                lifetime: 16,
+                rotation_interval: 4,
+                ..default()
+            },
+            Particles::HitSpark => Particle {
+                index: glyph_index('*').unwrap(),
+                fg: Palette::Lava,
+                spread: vec2(0.8, 0.8),
+                gravity: 0.03,
+                lifetime: 14,
+                rotation_interval: 3,
+                ..default()
+            },
+            Particles::EngineTrail => Particle {
+                index: glyph_index('°').unwrap(),
+                fg: Palette::Gravel,
+                velocity: vec2(0., -0.4),
+                spread: vec2(0.1, 0.05),
+                lifetime: 24,
+                ..default()
+            },
+        }
+    }
+}
+
+/// Spawns [Particle]-s every [interval] frames at the entity position + [offset].
+#[derive(Component)]
+pub struct ParticleEmitter {
+    pub particle: Particle,
+    pub interval: usize,
+    pub offset: Vec2,
+    frame: usize,
+}
+
+impl ParticleEmitter {
+    pub fn new(particle: Particle, interval: usize, offset: Vec2) -> Self {
+        Self { particle, interval, offset, frame: 0 }
+    }
+}
+
+/// [position] keeps the sub-pixel position, the transform is snapped to the pixel grid.
+#[derive(Component)]
+struct LiveParticle {
+    position: Vec2,
+    velocity: Vec2,
+    gravity: f32,
+    frame: usize,
+    lifetime: usize,
+    rotation_interval: usize,
+    /// (fg, bg) given back after [effects::FLASH] frames to particles spawned flashing
+    colors: Option<(Color, Color)>,
+}
+
+impl LiveParticle {
+    fn new(position: Vec2, velocity: Vec2, particle: &Particle) -> Self {
+        Self {
+            position,
+            velocity,
+            gravity: particle.gravity,
+            frame: 0,
+            lifetime: particle.lifetime,
+            rotation_interval: particle.rotation_interval,
+            colors: None,
+        }
+    }
+}
+
+/// Spawns [count] particles at [position], snapped to the pixel grid.
+pub fn burst(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    particle: Particle,
+    position: Vec2,
+    count: usize,
+) {
+    let mut rng = rand::thread_rng();
+    for _ in 0..count {
+        let velocity = particle.velocity + vec2(
+            rng.gen_range(-1f32..=1.) * particle.spread.x,
+            rng.gen_range(-1f32..=1.) * particle.spread.y,
+        );
+        let mut bundle = sprite(
+            particle.index, 0, 0, z_pos::PARTICLES,
+            particle.bg, particle.fg,
+            false, rng.gen_range(0..4),
+            atlas.clone(),
+        );
+        bundle.transform.translation.x = position.x.round();
+        bundle.transform.translation.y = position.y.round();
+        commands
+            .spawn(bundle)
+            .insert(LiveParticle::new(position, velocity, &particle))
+            .insert(NoAnimation);
+    }
+}
+
+/// Spawns a copy of [sprite] at [translation] moving like [particle], whose glyph and colors are ignored.
+/// The copy flashes during [effects::FLASH] frames before getting the colors of [sprite] back.
+pub fn spawn_tile(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    sprite: &TextModeTextureAtlasSprite,
+    translation: Vec3,
+    particle: Particle,
+) -> Entity {
+    let transparent: Color = Palette::Transparent.into();
+    let flash: Color = effects::FLASH_COLOR.into();
+
+    let mut flashing = sprite.clone();
+    flashing.fg = flash;
+    if sprite.bg != transparent { flashing.bg = flash; }
+
+    commands
+        .spawn(TextModeSpriteSheetBundle {
+            sprite: flashing,
+            texture_atlas: atlas.clone(),
+            transform: Transform::from_translation(translation.round()),
+            ..default()
+        })
+        .insert(LiveParticle { colors: Some((sprite.fg, sprite.bg)), ..LiveParticle::new(translation.truncate(), particle.velocity, &particle) })
+        .insert(NoAnimation)
+        .id()
+}
+
+fn emit_particles(
+    mut commands: Commands,
+    mut emitters: Query<(&mut ParticleEmitter, &GlobalTransform, &ComputedVisibility)>,
+    textures: Option<Res<Textures>>,
+) {
+    let Some(textures) = textures else { return; };
+
+    for (mut emitter, pos, visibility) in emitters.iter_mut() {
+        emitter.frame += 1;
+        if emitter.frame < emitter.interval || !visibility.is_visible_in_hierarchy() { continue; }
+        emitter.frame = 0;
+        let position = pos.translation().truncate() + emitter.offset;
+        burst(&mut commands, &textures.mrmotext, emitter.particle, position, 1);
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    mut particles: Query<(&mut LiveParticle, &mut Transform, &mut TextModeTextureAtlasSprite, Entity)>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for (mut particle, mut pos, mut sprite, id) in particles.iter_mut() {
+        particle.frame += 1;
+        if particle.frame >= particle.lifetime {
+            commands.entity(id).despawn_recursive();
+            continue;
+        }
+
+        particle.velocity.y -= particle.gravity;
+        let velocity = particle.velocity;
+        particle.position += velocity;
+        pos.translation.x = particle.position.x.round();
+        pos.translation.y = particle.position.y.round();
+
+        if particle.rotation_interval > 0 && particle.frame % particle.rotation_interval == 0 {
+            sprite.rotation = (sprite.rotation + 1) % 4;
+        }
+        if let (Some((fg, bg)), effects::FLASH) = (particle.colors, particle.frame) {
+            sprite.fg = fg;
+            sprite.bg = bg;
+        }
+        sprite.alpha = 1. - particle.frame as f32 / particle.lifetime as f32;
+    }
+}
+
+fn hit_sparks(
+    mut commands: Commands,
+    mut contacts: EventReader<Contact>,
+    textures: Option<Res<Textures>>,
+) {
+    let Some(textures) = textures else { return; };
+
+    for contact in contacts.iter() {
+        // Particles are anchored at their bottom left corner
+        let position = (contact.point - vec2(4., 4.)).round();
+        burst(&mut commands, &textures.mrmotext, Particles::HitSpark.particle(), position, particles::HIT_SPARKS);
+    }
+}
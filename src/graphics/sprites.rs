@@ -28,6 +28,44 @@ pub const SHIP: [TILE; 16] = [
     (2, 0, 231, 0, 14, true, 0),
 ];
 
+pub const SHIP_SCOUT: [TILE; 14] = [
+    (1, 4, 231, 0, 7, true, 2),
+    (2, 4, 231, 0, 7, false, 2),
+    (1, 3, 267, 6, 7, true, 2),
+    (2, 3, 267, 6, 7, false, 2),
+    (0, 2, 56, 0, 7, false, 0),
+    (1, 2, 877, 7, 1, false, 0),
+    (2, 2, 877, 7, 1, false, 0),
+    (3, 2, 56, 0, 7, true, 0),
+    (0, 1, 59, 0, 6, false, 0),
+    (1, 1, 267, 6, 7, true, 0),
+    (2, 1, 267, 6, 7, false, 0),
+    (3, 1, 59, 0, 6, true, 0),
+    (1, 0, 231, 0, 17, false, 0),
+    (2, 0, 231, 0, 17, true, 0),
+];
+
+pub const SHIP_TANK: [TILE; 18] = [
+    (0, 4, 59, 0, 8, false, 0),
+    (1, 4, 231, 0, 9, true, 2),
+    (2, 4, 231, 0, 9, false, 2),
+    (3, 4, 59, 0, 8, true, 0),
+    (0, 3, 56, 0, 8, false, 0),
+    (1, 3, 267, 8, 3, true, 2),
+    (2, 3, 267, 8, 3, false, 2),
+    (3, 3, 56, 0, 8, true, 0),
+    (0, 2, 56, 0, 8, false, 0),
+    (1, 2, 877, 9, 1, false, 0),
+    (2, 2, 877, 9, 1, false, 0),
+    (3, 2, 56, 0, 8, true, 0),
+    (0, 1, 59, 0, 8, false, 0),
+    (1, 1, 267, 8, 3, true, 0),
+    (2, 1, 267, 8, 3, false, 0),
+    (3, 1, 59, 0, 8, true, 0),
+    (1, 0, 231, 0, 14, false, 0),
+    (2, 0, 231, 0, 14, true, 0),
+];
+
 pub const STAR_FLY: [TILE; 9] = [
     (0, 2, 0, 0, 1, false, 0),
     (1, 2, 927, 0, 1, true, 0),
@@ -285,4 +323,19 @@ pub const BLOB: [TILE ; 36] = [
     (3, 0, 559, 0, 7, true, 0),
     (4, 0, 0, 0, 6, true, 0),
     (5, 0, 0, 0, 1, false, 0),
+];
+
+pub const SMALL_BLOB: [TILE ; 12] = [
+    (0, 2, 516, 0, 6, false, 0),
+    (1, 2, 228, 0, 6, false, 0),
+    (2, 2, 228, 0, 6, false, 0),
+    (3, 2, 516, 0, 6, true, 0),
+    (0, 1, 546, 0, 6, false, 0),
+    (1, 1, 720, 6, 16, true, 1),
+    (2, 1, 720, 6, 16, false, 1),
+    (3, 1, 546, 0, 6, true, 0),
+    (0, 0, 0, 0, 1, false, 0),
+    (1, 0, 554, 0, 7, true, 0),
+    (2, 0, 554, 0, 7, false, 0),
+    (3, 0, 0, 0, 1, false, 0),
 ];
\ No newline at end of file
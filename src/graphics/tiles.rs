@@ -2,6 +2,7 @@ use bevy::hierarchy::{Children, HierarchyQueryExt};
 use bevy::prelude::{Added, Commands, Component, Entity, Handle, Query, Transform};
 use bevy::sprite::TextureAtlas;
 use bevy_text_mode::{TextModeSpriteSheetBundle, TextModeTextureAtlasSprite};
+use serde::Deserialize;
 
 use crate::collision::SolidBody;
 use crate::graphics::sprites::ROTATION;
@@ -69,6 +70,7 @@ impl Tile {
     }
 }
 
+#[derive(Copy, Clone, Deserialize)]
 pub enum Tiles {
     LeftHand,
     Dash,
@@ -77,6 +79,21 @@ pub enum Tiles {
     LaserCannon,
     LaserPreparing,
     Laser,
+    Spear,
+    SpearTip,
+    Shotgun,
+    Pellet,
+    MissileLauncher,
+    Missile,
+    Bouncer,
+    Ball,
+    Flamethrower,
+    Flame,
+    Heart,
+    PowerUp,
+    Gem,
+    ShieldPickup,
+    BombPickup,
 }
 
 impl Tiles {
@@ -89,6 +106,21 @@ impl Tiles {
             Tiles::LaserCannon => Tile::from_index(451).with_rotation(Rotation::Left),
             Tiles::LaserPreparing => Tile::from_index(306),
             Tiles::Laser => Tile::from_index(336),
+            Tiles::Spear => Tile::from_index(892),
+            Tiles::SpearTip => Tile::from_index(893),
+            Tiles::Shotgun => Tile::from_index(1021),
+            Tiles::Pellet => Tile::from_index(878),
+            Tiles::MissileLauncher => Tile::from_index(451).with_rotation(Rotation::Right),
+            Tiles::Missile => Tile::from_index(900).flip(),
+            Tiles::Bouncer => Tile::from_index(896),
+            Tiles::Ball => Tile::from_index(911),
+            Tiles::Flamethrower => Tile::from_index(870),
+            Tiles::Flame => Tile::from_index(874),
+            Tiles::Heart => Tile::from_index(875),
+            Tiles::PowerUp => Tile::from_index(926),
+            Tiles::Gem => Tile::from_index(868),
+            Tiles::ShieldPickup => Tile::from_index(880),
+            Tiles::BombPickup => Tile::from_index(865),
         }
     }
 }
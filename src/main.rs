@@ -1,9 +1,13 @@
 use bevy::prelude::*;
 use bevy_text_mode::TextModePlugin;
 
+use crate::characters::behaviors::BehaviorsPlugin;
+use crate::characters::health::HealthPlugin;
 use crate::choose::ChoosePlugin;
 use crate::collision::CollisionPlugin;
+use crate::debug::DebugPlugin;
 use crate::graphics::GraphicsPlugin;
+use crate::pickups::PickupPlugin;
 use crate::screens::ScreensPlugin;
 use crate::util::{Palette, size};
 use crate::util::size::tile_to_f32;
@@ -18,12 +22,15 @@ mod rounds;
 mod progress;
 mod characters;
 mod screens;
+mod debug;
+mod pickups;
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 pub enum GameState {
     #[default]
     Loading,
     Title,
+    Hangar,
     Survival,
 }
 
@@ -52,25 +59,14 @@ fn main() {
         .add_plugin(GraphicsPlugin)
         .add_plugin(ChoosePlugin)
         .add_plugin(WeaponPlugin)
+        .add_plugin(PickupPlugin)
+        .add_plugin(HealthPlugin)
+        .add_plugin(BehaviorsPlugin)
         .add_plugin(ScreensPlugin)
-        .add_startup_system(init)
+        .add_plugin(DebugPlugin)
         .run();
 }
 
-fn init(mut commands: Commands) {
-    commands.spawn(Camera2dBundle {
-        transform: Transform {
-            scale: Vec3::new(1. / size::SCALE, 1. / size::SCALE, 1.),
-            translation: Vec3::new(
-                tile_to_f32(size::WIDTH) / 2.,
-                tile_to_f32(size::HEIGHT) / 2.,
-                100.),
-            ..Default::default()
-        },
-        ..Default::default()
-    });
-}
-
 #[derive(Bundle, Debug, Default)]
 pub struct MainBundle {
     pub transform: Transform,
@@ -0,0 +1,149 @@
+use bevy::prelude::*;
+use rand::prelude::SliceRandom;
+
+use crate::{GameState, MainBundle};
+use crate::characters::monsters::Monster;
+use crate::characters::ship::{Shielded, Ship};
+use crate::collision::{BodyType, CollisionSet, Contact, SingleContact, SolidBody};
+use crate::graphics::tiles::{Tile, Tiles};
+use crate::progress::Progress;
+use crate::screens::survival::{Life, Score, SurvivalUI};
+use crate::util::{Palette, pickups, z_pos};
+use crate::util::size::tile_to_f32;
+use crate::weapons::{ActiveWeapon, Arsenal, Weapon, WeaponChanged};
+
+/// Handles items dropped by monsters.
+///
+/// Pickups drift toward the ship rails and are collected by touching them.
+pub struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (move_pickups, collect_pickups.in_set(CollisionSet::Resolve))
+                .in_set(OnUpdate(GameState::Survival))
+        );
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Pickups {
+    /// +1 life
+    Heart,
+    /// +1 level for both active weapons
+    PowerUp,
+    /// Score bonus
+    Gem,
+    /// The ship takes no damage for a while
+    Shield,
+    /// +1 bomb
+    Bomb,
+}
+
+impl Pickups {
+    fn tile(&self) -> Tile {
+        let (tile, bg) = match self {
+            Pickups::Heart => (Tiles::Heart, Palette::Red),
+            Pickups::PowerUp => (Tiles::PowerUp, Palette::Cactus),
+            Pickups::Gem => (Tiles::Gem, Palette::Gold),
+            Pickups::Shield => (Tiles::ShieldPickup, Palette::Blue),
+            Pickups::Bomb => (Tiles::BombPickup, Palette::Terracotta),
+        };
+        Tile { bg, ..tile.to_tile().with_fg(Palette::Black) }
+    }
+}
+
+#[derive(Component)]
+pub struct Pickup {
+    kind: Pickups,
+    frame: usize,
+}
+
+/// Rolls [monster]'s drop table, the minion one if [minion], and spawns the pickup at [center].
+pub fn drop_pickup(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    monster: &Monster,
+    minion: bool,
+    center: Vec2,
+) {
+    let Ok(&(Some(kind), _)) = monster.drops(minion).choose_weighted(&mut rand::thread_rng(), |(_, weight)| *weight) else { return; };
+    commands
+        .spawn(Pickup { kind, frame: 0 })
+        .insert(MainBundle::from_xyz(center.x - tile_to_f32(1) / 2., center.y - tile_to_f32(1) / 2., z_pos::PICKUPS))
+        .insert(SolidBody {
+            body_type: BodyType::Pickup,
+            width: tile_to_f32(1),
+            height: tile_to_f32(1),
+            bottom_right_anchor: false,
+        })
+        .insert(SingleContact)
+        .insert(SurvivalUI)
+        .with_children(|builder| { builder.spawn(kind.tile().sprite(0, 0, 0., atlas)); });
+}
+
+fn move_pickups(
+    mut commands: Commands,
+    mut pickups: Query<(&mut Pickup, &mut Transform, &mut Visibility, Entity)>,
+    ship: Query<(&Transform, &SolidBody), (With<Ship>, Without<Pickup>)>,
+) {
+    let rail_x = ship
+        .get_single()
+        .map(|(pos, body)| pos.translation.x + body.width / 2. - tile_to_f32(1) / 2.)
+        .ok();
+
+    for (mut pickup, mut pos, mut visibility, id) in pickups.iter_mut() {
+        pickup.frame += 1;
+        if pickup.frame >= pickups::LIFETIME {
+            commands.entity(id).despawn_recursive();
+            continue;
+        }
+
+        // Drift toward the ship column
+        if let Some(rail_x) = rail_x {
+            let dx = rail_x - pos.translation.x;
+            pos.translation.x += dx.clamp(-pickups::SPEED, pickups::SPEED);
+        }
+
+        // Blink before disappearing
+        let blinking = pickup.frame + pickups::BLINK >= pickups::LIFETIME && (pickup.frame / pickups::BLINK_INTERVAL) % 2 == 0;
+        visibility.set_if_neq(if blinking { Visibility::Hidden } else { Visibility::Inherited });
+    }
+}
+
+fn collect_pickups(
+    mut commands: Commands,
+    mut contacts: EventReader<Contact>,
+    pickups: Query<&Pickup>,
+    mut life: Query<&mut Life>,
+    mut score: Query<&mut Score>,
+    mut weapons: Query<&mut ActiveWeapon>,
+    mut weapon_changed: EventWriter<WeaponChanged>,
+    mut progress: ResMut<Progress>,
+    arsenal: Res<Arsenal>,
+) {
+    for contact in contacts.iter() {
+        let Some((pickup, ship)) = contact.between(BodyType::Pickup, BodyType::Ship) else { continue };
+        let Ok(&Pickup { kind, .. }) = pickups.get(pickup.entity) else { continue };
+        commands.entity(pickup.entity).despawn_recursive();
+
+        match kind {
+            Pickups::Heart => if let Ok(mut life) = life.get_single_mut() {
+                life.0 = (life.0 + 1).min(progress.max_hp as i8);
+            },
+            Pickups::PowerUp => for mut active_weapon in weapons.iter_mut() {
+                let weapon = active_weapon.weapon;
+                let level = progress.upgrade_weapon(weapon.model);
+                active_weapon.weapon = Weapon { tile: weapon.tile, ..arsenal.weapon(weapon.model, level) };
+                weapon_changed.send(WeaponChanged(active_weapon.side, active_weapon.weapon));
+            },
+            Pickups::Gem => if let Ok(mut score) = score.get_single_mut() {
+                score.0 += pickups::GEM_SCORE;
+            },
+            Pickups::Shield => {
+                commands.entity(ship.entity).insert(Shielded(pickups::SHIELD_DURATION));
+            }
+            Pickups::Bomb => progress.add_bomb(),
+        }
+    }
+}
@@ -1,23 +1,169 @@
+use std::cmp::min;
+use std::collections::HashMap;
+
 use bevy::prelude::Resource;
+use strum::IntoEnumIterator;
 
+use crate::characters::ship::{Passive, Ships};
 use crate::util;
 use crate::weapons::Weapons;
 
 #[derive(Resource)]
 pub struct Progress {
+    pub ship: Ships,
     pub ship_speed: f32,
+    pub ship_acceleration: f32,
+    pub ship_friction: f32,
     pub damage_multiplier: f32,
     pub max_hp: u8,
     pub unlocked_weapons: Vec<Weapons>,
+    /// Weapons level, weapons missing are level 1
+    pub weapon_levels: HashMap<Weapons, u8>,
+    pub bombs: u8,
+    pub max_bombs: u8,
+    pub mode: Mode,
+    /// Continues left
+    pub credits: u8,
+    /// Continues used during the run
+    pub continues_used: u8,
 }
 
 impl Default for Progress {
     fn default() -> Self {
         Self {
+            ship: Ships::Rtemo,
             ship_speed: util::ship::SPEED,
+            ship_acceleration: util::ship::ACCELERATION,
+            ship_friction: util::ship::FRICTION,
             damage_multiplier: 1.0,
             max_hp: 3,
             unlocked_weapons: vec![Weapons::Finger],
+            weapon_levels: HashMap::new(),
+            bombs: 1,
+            max_bombs: 3,
+            mode: Mode::Normal,
+            credits: 0,
+            continues_used: 0,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Game mode chosen on the title screen.
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    Hard,
+}
+
+impl Mode {
+    pub fn credits(&self) -> u8 {
+        match self {
+            Mode::Normal => util::continues::NORMAL_CREDITS,
+            Mode::Hard => util::continues::HARD_CREDITS,
+        }
+    }
+
+    /// Returns the score kept after a continue.
+    pub fn score_after_continue(&self, score: i64) -> i64 {
+        match self {
+            Mode::Normal => score / 2,
+            Mode::Hard => 0,
+        }
+    }
+}
+
+pub struct HighScore {
+    pub score: i64,
+    pub ship: Ships,
+    pub mode: Mode,
+    pub continues: u8,
+}
+
+/// Best runs, highest score first.
+#[derive(Resource, Default)]
+pub struct HighScores(pub Vec<HighScore>);
+
+impl HighScores {
+    /// Records [entry] if it is among the best [util::continues::MAX_HIGH_SCORES] runs.
+    pub fn record(&mut self, entry: HighScore) {
+        let index = self.0.iter().position(|high_score| high_score.score < entry.score).unwrap_or(self.0.len());
+        self.0.insert(index, entry);
+        self.0.truncate(util::continues::MAX_HIGH_SCORES);
+    }
+
+    /// Returns the best run in [mode], if any.
+    pub fn best(&self, mode: Mode) -> Option<&HighScore> {
+        self.0.iter().find(|high_score| high_score.mode == mode)
+    }
+}
+
+/// Meta-progression, kept between runs.
+#[derive(Resource)]
+pub struct Unlocks {
+    pub ships: Vec<Ships>,
+}
+
+impl Default for Unlocks {
+    fn default() -> Self {
+        Self { ships: vec![Ships::Rtemo] }
+    }
+}
+
+impl Unlocks {
+    /// Unlocks the next locked ship, if any.
+    pub fn unlock_next_ship(&mut self) {
+        if let Some(ship) = Ships::iter().find(|ship| !self.ships.contains(ship)) {
+            self.ships.push(ship);
+        }
+    }
+}
+
+impl Progress {
+    /// Returns the progress at the start of a run with [ship] in [mode].
+    pub fn for_ship(ship: Ships, mode: Mode) -> Self {
+        let mut progress = Self {
+            ship,
+            ship_speed: ship.speed(),
+            max_hp: ship.max_hp(),
+            mode,
+            credits: mode.credits(),
+            ..Self::default()
+        };
+        if ship.passive() == Passive::Scavenger {
+            progress.bombs += 1;
+            progress.max_bombs += 1;
+        }
+        progress
+    }
+
+    pub fn weapon_level(&self, weapon: Weapons) -> u8 {
+        *self.weapon_levels.get(&weapon).unwrap_or(&1)
+    }
+
+    /// Increases the level of [weapon] and returns the new level.
+    pub fn upgrade_weapon(&mut self, weapon: Weapons) -> u8 {
+        let level = min(self.weapon_level(weapon) + 1, util::fight::MAX_WEAPON_LEVEL);
+        self.weapon_levels.insert(weapon, level);
+        level
+    }
+
+    /// Adds a bomb to the stock, up to [max_bombs].
+    pub fn add_bomb(&mut self) {
+        self.bombs = min(self.bombs + 1, self.max_bombs);
+    }
+}
+
+#[test]
+fn high_scores_keep_the_best_runs() {
+    let run = |score, mode| HighScore { score, ship: Ships::Rtemo, mode, continues: 0 };
+    let mut high_scores = HighScores::default();
+    for score in [30, 10, 50, 20, 40, 0, 60] { high_scores.record(run(score, Mode::Normal)); }
+    high_scores.record(run(45, Mode::Hard));
+
+    let scores: Vec<i64> = high_scores.0.iter().map(|high_score| high_score.score).collect();
+    assert_eq!(scores.len(), util::continues::MAX_HIGH_SCORES);
+    assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]), "High scores aren't sorted: {:?}", scores);
+    assert_eq!(scores[0], 60);
+    assert_eq!(high_scores.best(Mode::Hard).map(|high_score| high_score.score), Some(45));
+    assert_eq!(high_scores.best(Mode::Normal).map(|high_score| high_score.score), Some(60));
+}
@@ -1,13 +1,14 @@
 use bevy::math::vec2;
 use bevy::prelude::{Commands, Res, ResMut, Resource};
 use rand::prelude::IteratorRandom;
+use rand::Rng;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 use crate::{collision, util};
-use crate::characters::monsters::{Families, Monster, MonsterPath, Monsters, spawn_monster};
+use crate::characters::monsters::{Families, Modifiers, Monster, MonsterPath, Monsters, spawn_monster};
 use crate::screens::Textures;
-use crate::util::{Palette, Side};
+use crate::util::{modifiers, Palette, Side};
 use crate::util::size::{tile_to_f32, WIDTH};
 
 #[derive(EnumIter, Copy, Clone)]
@@ -47,7 +48,7 @@ impl MovementTypes {
     }
 }
 
-pub type RoundEvent = (Time, Side, Y, Monsters, Families, MovementTypes);
+pub type RoundEvent = (Time, Side, Y, Monsters, Families, MovementTypes, Modifiers);
 
 pub struct Time(u64);
 pub struct Y(usize);
@@ -73,36 +74,38 @@ pub fn update(
     if round.frame % util::fight::MONSTER_SPAWN_INTERVAL == 0 {
         if round.frame >= util::fight::MONSTER_SPAWN_INTERVAL * util::fight::BOSS_AFTER {
             // Spawn boss
-            let (side, monster, family, movement) = (
+            let (side, monster, family, movement, modifiers) = (
                 if rand::random() { Side::Left } else { Side::Right },
                 Monsters::random_boss(),
                 Families::Color(Palette::random()),
                 MovementTypes::Straight,
+                if rand::thread_rng().gen_bool(modifiers::ELITE_BOSS_CHANCE) { Modifiers::elite() } else { Modifiers::default() },
             );
 
             let body_size = collision::body_size(monster.sprite());
             let x = if side == Side::Left { -body_size.x } else { tile_to_f32(WIDTH) };
             let y = tile_to_f32(7);
 
-            let monster = Monster::new(monster, movement.to_path(side), x, y, side);
+            let monster = Monster::new(monster, movement.to_path(side), x, y, side).with_modifiers(modifiers);
             spawn_monster(&mut commands, &textures.mrmotext, monster, family, x, y);
 
             commands.remove_resource::<CurrentRound>();
         } else {
             // Spawn normal enemy
-            let (side, Y(y), monster, family, movement) = (
+            let (side, Y(y), monster, family, movement, modifiers) = (
                 if rand::random() { Side::Left } else { Side::Right },
                 Y(rand::random::<usize>() % 6 + 3),
                 Monsters::random_non_boss(),
                 Families::Color(Palette::random()),
                 MovementTypes::Random,
+                Modifiers::random(),
             );
 
             let body_size = collision::body_size(monster.sprite());
             let x = if side == Side::Left { -body_size.x } else { tile_to_f32(WIDTH) };
             let y = tile_to_f32(y + 3);
 
-            let monster = Monster::new(monster, movement.to_path(side), x, y, side);
+            let monster = Monster::new(monster, movement.to_path(side), x, y, side).with_modifiers(modifiers);
             spawn_monster(&mut commands, &textures.mrmotext, monster, family, x, y);
         }
     }
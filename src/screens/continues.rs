@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+
+use crate::GameState;
+use crate::graphics::text;
+use crate::graphics::text::color_text;
+use crate::graphics::transition::Transition;
+use crate::progress::{HighScore, HighScores, Progress};
+use crate::screens::survival::{Score, SurvivalUI};
+use crate::util::{continues, Palette, z_pos};
+
+/// Arcade-style continue prompt shown over the survival screen once the ship is wrecked.
+///
+/// Accepting a continue uses a credit and cuts the score (see [crate::progress::Mode]),
+/// the run ends when the countdown runs out or when there are no credits left.
+pub struct ContinuePlugin;
+
+impl Plugin for ContinuePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<HighScores>()
+            .add_event::<GameOver>()
+            .add_event::<Continued>()
+            .add_systems((start_countdown, update_countdown).in_set(OnUpdate(GameState::Survival)))
+            .add_system(cleanup.in_schedule(OnExit(GameState::Survival)));
+    }
+}
+
+/// Sent once the ship destruction sequence is over.
+pub struct GameOver;
+
+/// Sent when the player accepts a continue, the ship respawns.
+pub struct Continued;
+
+/// Frames elapsed since the continue prompt appeared.
+#[derive(Resource)]
+struct Countdown(usize);
+
+#[derive(Component)]
+struct ContinueUI;
+
+#[derive(Component)]
+struct CountdownText;
+
+fn start_countdown(
+    mut commands: Commands,
+    mut game_over: EventReader<GameOver>,
+    mut high_scores: ResMut<HighScores>,
+    progress: Res<Progress>,
+    score: Query<&Score>,
+) {
+    if game_over.iter().next().is_none() { return; }
+
+    if progress.credits == 0 {
+        end_run(&mut commands, &mut high_scores, &progress, score.get_single().map_or(0, |score| score.0));
+        return;
+    }
+
+    commands.insert_resource(Countdown(0));
+    for (line, x, y, color) in [
+        (format!("continue? {}", continues::COUNTDOWN), 11, continues::TEXT_Y, Palette::LightGold),
+        (format!("credits:{}", progress.credits), 12, continues::TEXT_Y - 2, Palette::LightTerracotta),
+        ("press space".to_string(), 10, continues::TEXT_Y - 3, Palette::LightTerracotta),
+    ] {
+        let mut entity = commands.spawn(color_text(&line, x, y, z_pos::GUI, Palette::Black, color));
+        entity.insert(ContinueUI).insert(SurvivalUI);
+        if y == continues::TEXT_Y { entity.insert(CountdownText); }
+    }
+}
+
+fn update_countdown(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    countdown: Option<ResMut<Countdown>>,
+    mut progress: ResMut<Progress>,
+    mut high_scores: ResMut<HighScores>,
+    mut score: Query<&mut Score>,
+    mut countdown_text: Query<&mut text::Text, With<CountdownText>>,
+    ui: Query<Entity, With<ContinueUI>>,
+    mut continued: EventWriter<Continued>,
+) {
+    let Some(mut countdown) = countdown else { return; };
+    countdown.0 += 1;
+    let seconds = continues::COUNTDOWN.saturating_sub(countdown.0 / continues::FRAMES_PER_SECOND);
+
+    if keys.just_pressed(continues::KEY) {
+        progress.credits -= 1;
+        progress.continues_used += 1;
+        if let Ok(mut score) = score.get_single_mut() {
+            score.0 = progress.mode.score_after_continue(score.0);
+        }
+        continued.send(Continued);
+    } else if seconds == 0 {
+        end_run(&mut commands, &mut high_scores, &progress, score.get_single().map_or(0, |score| score.0));
+    } else {
+        if let Ok(mut text) = countdown_text.get_single_mut() {
+            let countdown_text = format!("continue? {}", seconds);
+            if text.text != countdown_text { text.text = countdown_text; }
+        }
+        return;
+    }
+
+    commands.remove_resource::<Countdown>();
+    for e in &ui { commands.entity(e).despawn_recursive(); }
+}
+
+/// Records the run in the high scores and goes back to the title screen.
+fn end_run(
+    commands: &mut Commands,
+    high_scores: &mut HighScores,
+    progress: &Progress,
+    score: i64,
+) {
+    high_scores.record(HighScore {
+        score,
+        ship: progress.ship,
+        mode: progress.mode,
+        continues: progress.continues_used,
+    });
+    commands.insert_resource(Transition::to(GameState::Title).with_delay(continues::GAME_OVER_DELAY));
+}
+
+fn cleanup(mut commands: Commands) {
+    commands.remove_resource::<Countdown>();
+}
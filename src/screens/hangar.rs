@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use strum::IntoEnumIterator;
+
+use crate::{GameState, util};
+use crate::characters::ship::Ships;
+use crate::graphics::sprites::RTEMO_PALETTE;
+use crate::graphics::text::color_text;
+use crate::graphics::transition::Transition;
+use crate::progress::{Mode, Progress, Unlocks};
+use crate::screens::Textures;
+use crate::util::{hangar, Palette, z_pos};
+use crate::weapons::Arsenal;
+
+/// Ship selection before a run.
+///
+/// Ships are browsed with ← and →, locked ships can't be selected.
+pub struct HangarPlugin;
+
+impl Plugin for HangarPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Unlocks>()
+            .add_system(setup.in_schedule(OnEnter(GameState::Hangar)))
+            .add_system(update.in_set(OnUpdate(GameState::Hangar)))
+            .add_system(cleanup.in_schedule(OnExit(GameState::Hangar)));
+    }
+}
+
+/// Index of the selected ship in [Ships::iter].
+#[derive(Resource)]
+struct Hangar(usize);
+
+#[derive(Component)]
+struct HangarUI;
+
+fn setup(
+    mut commands: Commands,
+    textures: Res<Textures>,
+    unlocks: Res<Unlocks>,
+    arsenal: Res<Arsenal>,
+) {
+    commands.insert_resource(Hangar(0));
+    spawn_ship_info(&mut commands, &textures.mrmotext, Ships::Rtemo, &unlocks, &arsenal);
+}
+
+fn spawn_ship_info(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    ship: Ships,
+    unlocks: &Unlocks,
+    arsenal: &Arsenal,
+) {
+    let unlocked = unlocks.ships.contains(&ship);
+
+    // Ship preview, in black while locked
+    for &(x, y, i, bg, fg, flip, rotation) in ship.sprite() {
+        let (bg, fg) = if unlocked {
+            (RTEMO_PALETTE[bg], RTEMO_PALETTE[fg])
+        } else {
+            (if bg == 0 { Palette::Transparent } else { Palette::Black }, Palette::Black)
+        };
+        commands
+            .spawn(util::sprite(
+                i, hangar::PREVIEW_X + x, hangar::PREVIEW_Y + y, z_pos::GUI,
+                bg, fg, flip, rotation, atlas.clone(),
+            ))
+            .insert(HangarUI);
+    }
+
+    let name = if unlocked { ship.name() } else { "???" };
+    let mut lines = vec![(format!("← {} →", name), hangar::TITLE_Y, Palette::LightGold)];
+    if unlocked {
+        let (left, right) = ship.weapons();
+        lines.push((format!("hp:{} weapons:{} {}", ship.max_hp(), arsenal.weapon(left, 1).name, arsenal.weapon(right, 1).name), hangar::TEXT_Y + 2, Palette::LightTerracotta));
+        lines.push((format!("speed:{:.1}", ship.speed() / util::ship::SPEED), hangar::TEXT_Y + 1, Palette::LightTerracotta));
+        lines.push((format!("passive:{}", ship.passive().description()), hangar::TEXT_Y, Palette::LightTerracotta));
+        lines.push((hangar::TEXT_LAUNCH.to_string(), hangar::TEXT_Y - 1, Palette::LightGold));
+    } else {
+        lines.push((hangar::TEXT_LOCKED.to_string(), hangar::TEXT_Y, Palette::Gravel));
+    }
+
+    for (line, y, color) in lines {
+        commands
+            .spawn(color_text(&line, hangar::TEXT_X, y, z_pos::GUI, Palette::Transparent, color))
+            .insert(HangarUI);
+    }
+}
+
+fn update(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut hangar: ResMut<Hangar>,
+    ui: Query<Entity, With<HangarUI>>,
+    unlocks: Res<Unlocks>,
+    arsenal: Res<Arsenal>,
+    textures: Res<Textures>,
+    mode: Res<Mode>,
+    transition: Option<Res<Transition>>,
+) {
+    if transition.is_some() { return; }
+    let ships = Ships::iter().collect::<Vec<Ships>>();
+
+    let selected = if keys.just_pressed(KeyCode::Left) {
+        (hangar.0 + ships.len() - 1) % ships.len()
+    } else if keys.just_pressed(KeyCode::Right) {
+        (hangar.0 + 1) % ships.len()
+    } else {
+        hangar.0
+    };
+
+    if selected != hangar.0 {
+        hangar.0 = selected;
+        for e in &ui { commands.entity(e).despawn_recursive(); }
+        spawn_ship_info(&mut commands, &textures.mrmotext, ships[selected], &unlocks, &arsenal);
+    }
+
+    let ship = ships[hangar.0];
+    if keys.just_pressed(hangar::LAUNCH_KEY) && unlocks.ships.contains(&ship) {
+        commands.insert_resource(Progress::for_ship(ship, *mode));
+        commands.insert_resource(Transition::to(GameState::Survival));
+    }
+}
+
+fn cleanup(
+    mut commands: Commands,
+    ui: Query<Entity, With<HangarUI>>,
+) {
+    for e in &ui { commands.entity(e).despawn_recursive(); }
+    commands.remove_resource::<Hangar>();
+}
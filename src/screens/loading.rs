@@ -1,18 +1,23 @@
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
 
 use crate::GameState;
+use crate::weapons::{Arsenal, WeaponDefinitions};
 
 pub struct LoadingPlugin;
 
 impl Plugin for LoadingPlugin {
     fn build(&self, app: &mut App) {
         app
+            .add_plugin(RonAssetPlugin::<WeaponDefinitions>::new(&["weapons.ron"]))
             .add_loading_state(
                 LoadingState::new(GameState::Loading)
                     .continue_to_state(GameState::Title),
             )
-            .add_collection_to_loading_state::<_, Textures>(GameState::Loading);
+            .add_collection_to_loading_state::<_, Textures>(GameState::Loading)
+            .add_collection_to_loading_state::<_, Data>(GameState::Loading)
+            .init_resource_after_loading_state::<_, Arsenal>(GameState::Loading);
     }
 }
 
@@ -21,4 +26,9 @@ pub struct Textures {
     #[asset(texture_atlas(tile_size_x = 8., tile_size_y = 8., columns = 32, rows = 32, padding_x = 2., padding_y = 2.))]
     #[asset(path = "MRMOTEXT EX.png")]
     pub mrmotext: Handle<TextureAtlas>,
-}
\ No newline at end of file
+}
+#[derive(AssetCollection, Resource)]
+pub struct Data {
+    #[asset(path = "weapons.weapons.ron")]
+    pub weapons: Handle<WeaponDefinitions>,
+}
@@ -1,12 +1,16 @@
 use bevy::app::App;
 use bevy::prelude::Plugin;
 
-pub use loading::Textures;
+pub use loading::{Data, Textures};
 
+use crate::screens::continues::ContinuePlugin;
+use crate::screens::hangar::HangarPlugin;
 use crate::screens::loading::LoadingPlugin;
 use crate::screens::survival::SurvivalPlugin;
 use crate::screens::title::TitlePlugin;
 
+pub mod continues;
+mod hangar;
 mod loading;
 pub mod survival;
 mod title;
@@ -18,7 +22,9 @@ impl Plugin for ScreensPlugin {
         app
             .add_plugin(LoadingPlugin)
             .add_plugin(TitlePlugin)
+            .add_plugin(HangarPlugin)
             .add_plugin(SurvivalPlugin)
+            .add_plugin(ContinuePlugin)
         ;
     }
 }
\ No newline at end of file
@@ -3,17 +3,17 @@ use std::cmp::{max, min};
 use bevy::prelude::*;
 
 use crate::{GameState, rounds, util};
-use crate::characters::monsters::{monster_dies, move_monsters};
-use crate::characters::ship::{monsters_kill, Ship, ShipMoveEvent, spawn_ship, update_ship_image, update_ship_name, update_ship_y};
-use crate::collision::{add_invincible, collide};
+use crate::characters::monsters::{explode_bosses, monster_dies, move_monsters, regenerate_monsters};
+use crate::characters::ship::{Dash, monsters_kill, respawn_ship, ShipMoveEvent, ShipShield, spawn_ship, update_abilities, update_respawn, update_shield, update_ship_image, update_ship_name, update_ship_y, update_wreck, wreck_ship, Wrecked};
+use crate::collision::CollisionSet;
 use crate::graphics::background::Background;
 use crate::graphics::text;
 use crate::graphics::text::{color_text, text};
-use crate::graphics::transition::Transition;
+use crate::progress::Progress;
 use crate::rounds::CurrentRound;
 use crate::screens::Textures;
-use crate::util::{Palette, Side, z_pos};
-use crate::weapons::{monster_looses_life, spawn_weapon, WeaponChanged, Weapons};
+use crate::util::{Palette, Side, size, z_pos};
+use crate::weapons::{ActiveWeapon, Arsenal, Heat, monster_looses_life, spawn_weapon, WeaponChanged, WeaponSwap};
 
 pub struct SurvivalPlugin;
 
@@ -24,10 +24,12 @@ impl Plugin for SurvivalPlugin {
             .add_system(setup.in_schedule(OnEnter(GameState::Survival)))
             .add_systems(
                 (update_score, increase_score, update_life, update_ship_image, update_ship_y,
-                 update_ship_name, monster_looses_life, monster_dies, move_monsters, rounds::update,
-                 monsters_kill.after(add_invincible).after(collide), game_over.after(add_invincible))
+                 update_ship_name, monster_looses_life.in_set(CollisionSet::Damage), monster_dies.in_set(CollisionSet::Resolve),
+                 explode_bosses, move_monsters, rounds::update, monsters_kill.in_set(CollisionSet::Damage), wreck_ship.in_set(CollisionSet::Resolve),
+                 update_weapon_levels, update_heat_gauges)
                     .in_set(OnUpdate(GameState::Survival))
             )
+            .add_systems((update_swap_gauge, update_shield, update_bombs, update_abilities, update_ability_gauges, update_wreck, respawn_ship, update_respawn, regenerate_monsters).in_set(OnUpdate(GameState::Survival)))
             .add_system(cleanup.in_schedule(OnExit(GameState::Survival)));
     }
 }
@@ -36,11 +38,29 @@ impl Plugin for SurvivalPlugin {
 pub struct SurvivalUI;
 
 #[derive(Component)]
-pub struct Score(i64);
+pub struct Score(pub i64);
 
 #[derive(Component)]
 pub struct Life(pub(crate) i8);
 
+#[derive(Component)]
+pub struct WeaponLevel(Side);
+
+#[derive(Component)]
+pub struct HeatGauge(Side);
+
+#[derive(Component)]
+pub struct SwapGauge;
+
+#[derive(Component)]
+pub struct BombStock;
+
+#[derive(Component)]
+pub struct DashGauge;
+
+#[derive(Component)]
+pub struct ShieldGauge;
+
 const LIFE_TEXTS: [&str; 6] = ["°°°°°", "•°°°°", "••°°°", "•••°°", "••••°", "•••••"];
 
 
@@ -48,10 +68,14 @@ fn setup(
     mut commands: Commands,
     textures: Res<Textures>,
     mut weapon_changed: EventWriter<WeaponChanged>,
+    progress: Res<Progress>,
+    arsenal: Res<Arsenal>,
 ) {
-    spawn_ship(&mut commands, &textures.mrmotext);
-    spawn_weapon(Weapons::Laser, Side::Left, &mut commands, &textures.mrmotext, &mut weapon_changed);
-    spawn_weapon(Weapons::Finger, Side::Right, &mut commands, &textures.mrmotext, &mut weapon_changed);
+    spawn_ship(&mut commands, &textures.mrmotext, progress.ship);
+    let (left, right) = progress.ship.weapons();
+    for (weapon, side) in [(left, Side::Left), (right, Side::Right)] {
+        spawn_weapon(weapon, progress.weapon_level(weapon), side, &mut commands, &textures.mrmotext, &arsenal, &mut weapon_changed);
+    }
 
     commands
         .spawn(text("score[000000]", 3, 1, z_pos::GUI))
@@ -62,11 +86,39 @@ fn setup(
         .insert(SurvivalUI);
     commands
         .spawn(color_text(LIFE_TEXTS[0], 23, 1, z_pos::GUI, Palette::Transparent, Palette::Red))
-        .insert(Life(3))
+        .insert(Life(progress.max_hp as i8))
         .insert(SurvivalUI);
     commands
         .spawn(text("]", 28, 1, z_pos::GUI))
         .insert(SurvivalUI);
+    for (side, x) in [(Side::Left, 3), (Side::Right, size::WIDTH - 11)] {
+        commands
+            .spawn(text("", x, size::HEIGHT - 1, z_pos::GUI))
+            .insert(WeaponLevel(side))
+            .insert(SurvivalUI);
+    }
+    for (side, x) in [(Side::Left, 12), (Side::Right, size::WIDTH - 16)] {
+        commands
+            .spawn(color_text("", x, size::HEIGHT - 1, z_pos::GUI, Palette::Black, Palette::Lava))
+            .insert(HeatGauge(side))
+            .insert(SurvivalUI);
+    }
+    commands
+        .spawn(color_text("", size::WIDTH / 2 - 3, 0, z_pos::GUI, Palette::Black, Palette::LightGold))
+        .insert(SwapGauge)
+        .insert(SurvivalUI);
+    commands
+        .spawn(text("", 1, 0, z_pos::GUI))
+        .insert(BombStock)
+        .insert(SurvivalUI);
+    commands
+        .spawn(color_text("", 19, 0, z_pos::GUI, Palette::Black, Palette::LightGold))
+        .insert(DashGauge)
+        .insert(SurvivalUI);
+    commands
+        .spawn(color_text("", 25, 0, z_pos::GUI, Palette::Black, util::ship::SHIELD_COLOR))
+        .insert(ShieldGauge)
+        .insert(SurvivalUI);
 
     // Round
     commands.insert_resource(CurrentRound::new());
@@ -75,7 +127,9 @@ fn setup(
 fn increase_score(
     time: Res<Time>,
     mut query: Query<&mut Score>,
+    wrecked: Query<(), With<Wrecked>>,
 ) {
+    if !wrecked.is_empty() { return; }
     let mut score = query.single_mut();
     score.0 += time.delta().as_millis() as i64;
 }
@@ -97,18 +151,78 @@ fn update_life(
     }
 }
 
-fn game_over(
-    mut commands: Commands,
-    lives: Query<&Life, Changed<Life>>,
-    ship: Query<&Ship>,
+fn update_weapon_levels(
+    mut weapon_changed: EventReader<WeaponChanged>,
+    mut query: Query<(&mut text::Text, &WeaponLevel)>,
 ) {
-    if let Ok(&Life(lives)) = lives.get_single() {
-        if lives <= 0 && ship.get_single().is_ok() {
-            commands.insert_resource(Transition::to(GameState::Title).with_delay(util::fight::GAME_OVER_DELAY));
+    for WeaponChanged(side, weapon) in weapon_changed.iter() {
+        for (mut text, level) in query.iter_mut() {
+            if level.0 != *side { continue; }
+            let level = weapon.level as usize;
+            text.text = format!("{}[{}{}]", weapon.name, "•".repeat(level), "°".repeat(util::fight::MAX_WEAPON_LEVEL as usize - level));
         }
     }
 }
 
+fn update_heat_gauges(
+    weapons: Query<(&ActiveWeapon, &Heat)>,
+    mut gauges: Query<(&mut text::Text, &HeatGauge)>,
+) {
+    for (mut text, gauge) in gauges.iter_mut() {
+        let Some((_, heat)) = weapons.iter().find(|(weapon, _)| weapon.side == gauge.0) else { continue };
+        let gauge_text = if heat.overheated > 0 { "hot!".to_string() } else {
+            let width = util::fight::HEAT_GAUGE_WIDTH;
+            let filled = ((heat.value * width as f32).ceil() as usize).min(width);
+            format!("{}{}", "•".repeat(filled), "°".repeat(width - filled))
+        };
+        if text.text != gauge_text { text.text = gauge_text; }
+    }
+}
+
+fn update_swap_gauge(
+    swap: Res<WeaponSwap>,
+    mut gauge: Query<&mut text::Text, With<SwapGauge>>,
+) {
+    let Ok(mut text) = gauge.get_single_mut() else { return; };
+    let gauge_text = cooldown_gauge('↔', swap.cooldown as usize, util::fight::SWAP_COOLDOWN as usize);
+    if text.text != gauge_text { text.text = gauge_text; }
+}
+
+/// Returns [icon] followed by a gauge filling up as [cooldown] decreases to 0.
+fn cooldown_gauge(icon: char, cooldown: usize, total: usize) -> String {
+    let width = util::fight::COOLDOWN_GAUGE_WIDTH;
+    let remaining = (cooldown as f32 / total as f32 * width as f32).ceil() as usize;
+    let filled = width - remaining.min(width);
+    format!("{}{}{}", icon, "•".repeat(filled), "°".repeat(width - filled))
+}
+
+fn update_ability_gauges(
+    progress: Res<Progress>,
+    ship: Query<(&Dash, &ShipShield)>,
+    mut dash_gauge: Query<&mut text::Text, (With<DashGauge>, Without<ShieldGauge>)>,
+    mut shield_gauge: Query<&mut text::Text, (With<ShieldGauge>, Without<DashGauge>)>,
+) {
+    let Ok((dash, shield)) = ship.get_single() else { return; };
+    if let Ok(mut text) = dash_gauge.get_single_mut() {
+        let gauge_text = cooldown_gauge('^', dash.cooldown, progress.ship.dash_cooldown());
+        if text.text != gauge_text { text.text = gauge_text; }
+    }
+    if let Ok(mut text) = shield_gauge.get_single_mut() {
+        let gauge_text = cooldown_gauge('#', if shield.charged { 0 } else { shield.cooldown }, progress.ship.shield_regen());
+        if text.text != gauge_text { text.text = gauge_text; }
+    }
+}
+
+fn update_bombs(
+    progress: Res<Progress>,
+    mut stock: Query<&mut text::Text, With<BombStock>>,
+) {
+    let Ok(mut text) = stock.get_single_mut() else { return; };
+    let bombs = progress.bombs.min(progress.max_bombs) as usize;
+    let stock_text = format!("bomb[{}{}]", "•".repeat(bombs), "°".repeat(progress.max_bombs as usize - bombs));
+    if text.text != stock_text { text.text = stock_text; }
+}
+
 fn cleanup(
     mut commands: Commands,
     query: Query<Entity, With<SurvivalUI>>,
@@ -8,7 +8,7 @@ use crate::graphics::background::Background;
 use crate::graphics::frame::spawn_frame;
 use crate::graphics::text::color_text;
 use crate::graphics::transition::Transition;
-use crate::progress::Progress;
+use crate::progress::{HighScores, Mode};
 use crate::screens::Textures;
 use crate::util::{Palette, Side, z_pos};
 
@@ -28,6 +28,7 @@ impl Plugin for TitlePlugin {
 
 fn setup(
     mut commands: Commands,
+    high_scores: Res<HighScores>,
     textures: Res<Textures>,
 ) {
     for (t, x, y) in [
@@ -48,6 +49,19 @@ fn setup(
             .insert(TitleUI);
     }
 
+    // Best run of each mode
+    for (mode, x) in [(Mode::Normal, 4), (Mode::Hard, 19)] {
+        let Some(best) = high_scores.best(mode) else { continue };
+        for (t, y) in [
+            (format!("best {}", best.score), 4),
+            (format!("{} {} cont", best.ship.name(), best.continues), 3),
+        ] {
+            commands
+                .spawn(color_text(&t, x, y, z_pos::BACKGROUND_TEXT, Palette::Transparent, Palette::LightGold))
+                .insert(TitleUI);
+        }
+    }
+
     spawn_frame(&mut commands, &textures.mrmotext);
 
     background::spawn_rails(&mut commands, &textures.mrmotext);
@@ -60,9 +74,8 @@ fn exit_title(
     mut selection: EventReader<Select>,
 ) {
     for Select(side) in selection.iter() {
-        commands.insert_resource(Progress::default());
-        // TODO: Different game mode
-        commands.insert_resource(Transition::to(GameState::Survival));
+        commands.insert_resource(if *side == Side::Left { Mode::Normal } else { Mode::Hard });
+        commands.insert_resource(Transition::to(GameState::Hangar));
     }
 }
 
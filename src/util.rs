@@ -6,6 +6,7 @@ use bevy::utils::default;
 use bevy_text_mode::{TextModeSpriteSheetBundle, TextModeTextureAtlasSprite};
 use lazy_static::lazy_static;
 use rand::prelude::SliceRandom;
+use serde::Deserialize;
 
 use size::{HEIGHT, tile_to_f32, WIDTH};
 
@@ -17,6 +18,11 @@ pub mod size {
     pub const WIDTH: usize = 32;
     pub const HEIGHT: usize = 18;
 
+    /// First row inside the frame
+    pub const FRAME_BOTTOM: usize = 3;
+    /// First row of the top frame border
+    pub const FRAME_TOP: usize = HEIGHT - 1;
+
     /// Returns world coordinates for a tile, for instance `2` -> `(2 * TILE_SIZE) as f32 `.
     pub const fn tile_to_f32(tile: usize) -> f32 { (tile * TILE_SIZE) as f32 }
 }
@@ -25,14 +31,20 @@ pub mod z_pos {
     pub const BACKGROUND: f32 = 0.;
     pub const RAILS: f32 = 1.;
     pub const ENEMIES: f32 = 2.;
+    pub const HEALTH_BARS: f32 = 2.5;
+    pub const PICKUPS: f32 = 3.;
     pub const SHOTS: f32 = 4.;
+    pub const PARTICLES: f32 = 5.;
+    pub const DAMAGE_NUMBERS: f32 = 6.;
     pub const WEAPONS: f32 = 7.;
     pub const MACHINE: f32 = 8.;
     pub const BACKGROUND_TEXT: f32 = 8.5;
     pub const TRANSITION: f32 = 9.;
     pub const FRAME: f32 = 10.;
+    pub const FLASH: f32 = 11.;
     pub const GUI: f32 = 12.;
     pub const CHOOSE_BORDER: f32 = 14.;
+    pub const DEBUG: f32 = 16.;
 }
 
 pub mod fight {
@@ -40,8 +52,34 @@ pub mod fight {
     pub const MONSTERS_FREEZE: usize = 40;
     pub const LASER_LOADING: usize = 30;
     pub const LASER_FIRING: usize = 45;
+    pub const LASER_FIRING_PER_LEVEL: usize = 15;
+    pub const FLAME_LIFETIME: usize = 28;
+
+    /// Frames before the charge is displayed on the weapon
+    pub const CHARGE_VISUAL_DELAY: u16 = 15;
+    pub const CHARGE_DAMAGE_MULTIPLIER: i16 = 3;
+    /// Size of charged shots, in tiles
+    pub const CHARGED_SHOT_SIZE: usize = 2;
+
+    /// Frames an overheated weapon is locked
+    pub const OVERHEAT_PENALTY: u16 = 120;
+    pub const HEAT_GAUGE_WIDTH: usize = 4;
+    /// Width of the swap, dash and shield cooldown gauges
+    pub const COOLDOWN_GAUGE_WIDTH: usize = 4;
+
+    pub const SWAP_COOLDOWN: u16 = 90;
+    /// Frames during which the next shot of each weapon is empowered after a swap
+    pub const SWAP_BONUS_WINDOW: u16 = 60;
+
+    pub const MAX_WEAPON_LEVEL: u8 = 5;
+    /// Cooldown reduction per weapon level (ratio of the base cooldown)
+    pub const COOLDOWN_PER_LEVEL: f32 = 0.1;
+    /// Vertical speed increase per weapon level (ratio of the base vertical speed)
+    pub const SPREAD_PER_LEVEL: f32 = 0.15;
+    /// Max angle (radians) a homing shot can turn each frame
+    pub const HOMING_TURN_RATE: f32 = 0.04;
+    pub const HOMING_LIFETIME: usize = 300;
 
-    pub const GAME_OVER_DELAY: u64 = 2000;
     pub const MONSTER_SPAWN_INTERVAL: u64 = 400;
     pub const BOSS_AFTER: u64 = 5;
 }
@@ -53,8 +91,174 @@ pub mod background {
     pub const LAYOUT_HEIGHT: usize = 13;
 }
 
+pub mod effects {
+    use crate::util::Palette;
+
+    pub const DEBRIS_LIFETIME: usize = 60;
+    pub const DEBRIS_SPEED: f32 = 1.;
+    pub const DEBRIS_GRAVITY: f32 = 0.05;
+    /// Duration of the palette flash when a monster dies
+    pub const FLASH: usize = 6;
+    pub const FLASH_COLOR: Palette = Palette::LightGold;
+    pub const EXPLOSION_LIFETIME: usize = 25;
+    pub const EXPLOSION_TILES: usize = 8;
+    pub const BOSS_EXPLOSIONS: usize = 5;
+    pub const BOSS_EXPLOSION_INTERVAL: usize = 12;
+}
+
+pub mod particles {
+    pub const MUZZLE_FLASH: usize = 2;
+    pub const HIT_SPARKS: usize = 3;
+    pub const LASER_SPARK_INTERVAL: usize = 3;
+    pub const ENGINE_TRAIL_INTERVAL: usize = 6;
+}
+
+pub mod pickups {
+    /// Horizontal speed of pickups drifting toward the ship column
+    pub const SPEED: f32 = 0.4;
+    pub const LIFETIME: usize = 600;
+    /// Pickups blink during their last [BLINK] frames
+    pub const BLINK: usize = 120;
+    pub const BLINK_INTERVAL: usize = 8;
+    pub const GEM_SCORE: i64 = 50_000;
+    pub const SHIELD_DURATION: usize = 300;
+}
+
+pub mod modifiers {
+    use crate::util::Palette;
+
+    /// Damage removed from each hit on armored monsters
+    pub const ARMOR: i16 = 1;
+    /// Background of the tiles of armored monsters
+    pub const ARMOR_COLOR: Palette = Palette::Rock;
+    /// Background of the tiles on the shielded side of monsters
+    pub const SHIELD_COLOR: Palette = Palette::LightBlue;
+    /// Frames for a regenerating monster to regain a life
+    pub const REGENERATION_INTERVAL: usize = 90;
+    pub const ELITE_HP_MULTIPLIER: i16 = 2;
+    pub const ELITE_COLOR: Palette = Palette::LightPurple;
+    pub const ARMOR_CHANCE: f64 = 0.15;
+    pub const FRONT_SHIELD_CHANCE: f64 = 0.1;
+    pub const REGENERATION_CHANCE: f64 = 0.1;
+    pub const ELITE_CHANCE: f64 = 0.08;
+    pub const ELITE_BOSS_CHANCE: f64 = 0.25;
+}
+
+pub mod behaviors {
+    use crate::characters::monsters::Monsters;
+    use crate::util::Palette;
+
+    pub const SPLIT_GENERATIONS: u8 = 2;
+    /// Tiles between the two halves of a split monster
+    pub const SPLIT_DISTANCE: usize = 1;
+    pub const SUMMONED: Monsters = Monsters::Skulleton;
+    pub const SUMMON_INTERVAL: usize = 240;
+    pub const SUMMON_MAX: usize = 3;
+    pub const SUMMON_HP: i16 = 3;
+    pub const SUMMON_COLOR: Palette = Palette::Gravel;
+    pub const FLAME_INTERVAL: usize = 50;
+    pub const FLAME_LIFETIME: usize = 300;
+    /// Flames fade out during their last [FLAME_FADE] frames
+    pub const FLAME_FADE: usize = 60;
+    pub const FLAME_COLOR: Palette = Palette::Lava;
+    /// Bursts last [BURST_FRAMES] every [BURST_PERIOD] frames
+    pub const BURST_PERIOD: usize = 60;
+    pub const BURST_FRAMES: usize = 15;
+    /// Horizontal distance at which shots are dodged, in pixels
+    pub const DODGE_DISTANCE: f32 = 24.;
+    pub const DODGE_FRAMES: usize = 8;
+    pub const DODGE_SPEED: f32 = 1.;
+    pub const DODGE_COOLDOWN: usize = 60;
+    pub const SEEKER_INTERVAL: usize = 180;
+    pub const SEEKER_SPEED: f32 = 0.8;
+    pub const SEEKER_COLOR: Palette = Palette::LightPurple;
+}
+
+pub mod health {
+    use bevy::prelude::KeyCode;
+
+    use crate::util::Palette;
+
+    pub const TOGGLE_KEY: KeyCode = KeyCode::H;
+    /// Only monsters with more lives get a health bar
+    pub const BAR_MIN_HP: i16 = 5;
+    pub const BAR_HEIGHT: f32 = 1.;
+    /// Pixels between the monster and its health bar
+    pub const BAR_OFFSET: f32 = 2.;
+    pub const BAR_BG: Palette = Palette::Black;
+    pub const BAR_FG: Palette = Palette::Red;
+    pub const NUMBER_LIFETIME: usize = 40;
+    /// Damage numbers rise by a pixel every [NUMBER_RISE_INTERVAL] frames
+    pub const NUMBER_RISE_INTERVAL: usize = 3;
+    pub const NUMBER_COLOR: Palette = Palette::LightGold;
+
+    /// Durations in frames of the tint of a monster hit
+    pub const HIT_FLASH: usize = 6;
+    pub const KILL_FLASH: usize = 16;
+    pub const HIT_FLASH_COLOR: Palette = Palette::LightGold;
+    pub const KILL_FLASH_COLOR: Palette = Palette::Red;
+    /// Recoil distances in pixels
+    pub const RECOIL: f32 = 2.;
+    pub const KILL_RECOIL: f32 = 5.;
+    pub const RECOIL_FRAMES: usize = 10;
+}
+
+pub mod bomb {
+    use bevy::prelude::KeyCode;
+
+    pub const KEY: KeyCode = KeyCode::B;
+    pub const DAMAGE: i16 = 8;
+    pub const SHIP_INVINCIBILITY: usize = 90;
+    pub const TRAUMA: f32 = 0.6;
+    pub const FLASH: usize = 20;
+    /// Delay of the wave for each tile away from the ship
+    pub const WAVE_FRAMES_PER_TILE: f32 = 1.5;
+    pub const TILE_LIFETIME: usize = 18;
+}
+
 pub mod misc {
     pub const ANIMATION_INTERVAL: usize = 80;
+    /// Full block tile, scaled to draw rectangles
+    pub const BLOCK_TILE: usize = 1023;
+}
+
+pub mod camera {
+    use bevy::prelude::KeyCode;
+
+    /// Cycles through [INTENSITIES]
+    pub const INTENSITY_KEY: KeyCode = KeyCode::F2;
+    /// Effects intensities, from full to disabled
+    pub const INTENSITIES: [f32; 3] = [1., 0.5, 0.];
+    /// Max shake offset in pixels
+    pub const MAX_SHAKE: f32 = 4.;
+    pub const TRAUMA_DECAY: f32 = 0.02;
+    pub const ZOOM_DECAY: f32 = 0.01;
+    pub const FLASH_ALPHA: f32 = 0.5;
+
+    pub const SHIP_HIT_TRAUMA: f32 = 0.6;
+    pub const SHIP_HIT_FLASH: usize = 10;
+    pub const LASER_TRAUMA: f32 = 0.25;
+    pub const LASER_ZOOM: f32 = 0.2;
+    pub const BOSS_EXPLOSION_TRAUMA: f32 = 0.3;
+    pub const BOSS_DEATH_TRAUMA: f32 = 1.;
+    pub const BOSS_DEATH_ZOOM: f32 = 0.2;
+    pub const BOSS_DEATH_FLASH: usize = 20;
+    /// Moving things only update once every [SLOW_MOTION_RATE] frames during slow motion
+    pub const SLOW_MOTION_RATE: usize = 3;
+    pub const SHIP_DEATH_TRAUMA: f32 = 1.;
+    pub const SHIP_DEATH_FLASH: usize = 30;
+    pub const SHIP_DEATH_SLOW_MOTION: usize = 90;
+}
+
+pub mod debug {
+    use bevy::prelude::KeyCode;
+
+    pub const TOGGLE_KEY: KeyCode = KeyCode::F1;
+    pub const BODY_ALPHA: f32 = 0.8;
+    pub const HITBOX_ALPHA: f32 = 0.35;
+    pub const CONTACT_ALPHA: f32 = 0.6;
+    /// Row of the body count, above the score
+    pub const BODY_COUNT_Y: usize = 2;
 }
 
 pub mod choose {
@@ -68,6 +272,34 @@ pub mod choose {
     pub const SIDE_Y: usize = 15;
 }
 
+pub mod hangar {
+    use bevy::prelude::KeyCode;
+
+    pub const TEXT_LAUNCH: &'static str = "Press space to launch";
+    pub const TEXT_LOCKED: &'static str = "Defeat a boss to unlock";
+    pub const LAUNCH_KEY: KeyCode = KeyCode::Space;
+    pub const PREVIEW_X: usize = 14;
+    pub const PREVIEW_Y: usize = 9;
+    pub const TITLE_Y: usize = 15;
+    pub const TEXT_X: usize = 4;
+    pub const TEXT_Y: usize = 5;
+}
+
+pub mod continues {
+    use bevy::prelude::KeyCode;
+
+    pub const KEY: KeyCode = KeyCode::Space;
+    pub const NORMAL_CREDITS: u8 = 3;
+    pub const HARD_CREDITS: u8 = 1;
+    /// Seconds to accept a continue
+    pub const COUNTDOWN: usize = 9;
+    pub const FRAMES_PER_SECOND: usize = 60;
+    pub const MAX_HIGH_SCORES: usize = 5;
+    /// Milliseconds before going back to the title screen once the run is over
+    pub const GAME_OVER_DELAY: u64 = 1000;
+    pub const TEXT_Y: usize = 10;
+}
+
 pub mod transition {
     use crate::util::size::HEIGHT;
 
@@ -80,7 +312,7 @@ pub mod tweening {
     pub const DELAY: u64 = 200;
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Eq, PartialEq, Deserialize)]
 pub enum Palette {
     Black = 0,
 
@@ -178,13 +410,47 @@ pub fn is_oob(transform: &Transform) -> bool {
 }
 
 pub mod ship {
+    use bevy::prelude::{KeyCode, Vec2};
+
+    use crate::util::Palette;
     use crate::util::size::{HEIGHT, tile_to_f32, WIDTH};
 
+    /// Max speed, in pixels per frame
     pub const SPEED: f32 = 0.3;
+    /// Speed gained per frame while moving, in pixels per frame
+    pub const ACCELERATION: f32 = 0.04;
+    /// Ratio of the speed lost per frame when not moving
+    pub const FRICTION: f32 = 0.2;
+    /// Analog inputs below this magnitude are ignored
+    pub const DEAD_ZONE: f32 = 0.15;
     pub const INIT_Y: f32 = tile_to_f32(HEIGHT / 2 - 2);
-    pub const MAX_Y: i64 = 190;
-    pub const MIN_Y: i64 = -170;
+    /// Bounds of the ship offset from [INIT_Y], in pixels
+    pub const MAX_Y: f32 = 57.;
+    pub const MIN_Y: f32 = -51.;
     pub const LASER_LENGTH: usize = WIDTH / 2 - 3;
+    pub const LASER_LENGTH_PER_LEVEL: usize = 3;
+
+    pub const DASH_KEY: KeyCode = KeyCode::LShift;
+    pub const DASH_FRAMES: usize = 8;
+    /// Ship speed while dashing, in pixels per frame
+    pub const DASH_SPEED: f32 = 1.8;
+    pub const DASH_COOLDOWN: usize = 90;
+    pub const DASH_COLOR: Palette = Palette::LightGold;
+    /// Frames for the shield to come back after absorbing a hit
+    pub const SHIELD_REGEN: usize = 600;
+    pub const SHIELD_COLOR: Palette = Palette::LightBlue;
+
+    /// Frames between the ship destruction and the respawn or game over
+    pub const WRECK_FRAMES: usize = 150;
+    /// Velocity of the left weapon tumbling away, mirrored for the right one
+    pub const WEAPON_TUMBLE_SPEED: Vec2 = Vec2::new(0.8, 1.2);
+    /// Frames between two quarter turns of the weapons tumbling away
+    pub const WEAPON_TUMBLE_INTERVAL: usize = 5;
+    /// Offset from [INIT_Y] the ship slides from when respawning
+    pub const RESPAWN_Y: f32 = -tile_to_f32(HEIGHT / 2 + 4);
+    /// Sliding speed when respawning, in pixels per frame
+    pub const RESPAWN_SPEED: f32 = 1.2;
+    pub const RESPAWN_INVINCIBILITY: usize = 180;
 }
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use bevy_text_mode::TextModeTextureAtlasSprite;
+
+use crate::characters::monsters::Monster;
+use crate::characters::ship::Ship;
+use crate::collision::{BodyType, Invincible, SolidBody};
+use crate::graphics::animation::NoAnimation;
+use crate::graphics::camera::CameraEffects;
+use crate::graphics::text::glyph_index;
+use crate::progress::Progress;
+use crate::screens::survival::SurvivalUI;
+use crate::screens::Textures;
+use crate::util::{bomb, fight, is_oob, Palette, size, sprite, z_pos};
+use crate::util::size::tile_to_f32;
+
+/// Tile of the screen-wide bomb wave, shown after [delay] frames.
+#[derive(Component)]
+pub struct BombTile {
+    delay: usize,
+    frame: usize,
+}
+
+/// Uses a bomb from the stock: damages every monster on screen, destroys enemy shots
+/// and makes the ship invincible for a while.
+pub fn drop_bomb(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut progress: ResMut<Progress>,
+    mut monsters: Query<(&mut Monster, &Transform, Option<&Invincible>, Entity)>,
+    bodies: Query<(&SolidBody, Entity)>,
+    ship: Query<(&Transform, &SolidBody, Option<&Invincible>, Entity), With<Ship>>,
+    mut camera_effects: ResMut<CameraEffects>,
+    textures: Res<Textures>,
+) {
+    if !keys.just_pressed(bomb::KEY) || progress.bombs == 0 { return; }
+    let Ok((ship_pos, ship_body, ship_invincible, ship_id)) = ship.get_single() else { return; };
+    progress.bombs -= 1;
+
+    for (mut monster, pos, invincible, id) in monsters.iter_mut() {
+        if is_oob(pos) { continue; }
+        monster.lives -= bomb::DAMAGE;
+        if invincible.is_none() { commands.entity(id).insert(Invincible(fight::ENEMY_COOLDOWN)); }
+    }
+
+    for (body, id) in bodies.iter() {
+        if body.body_type == BodyType::EnemyShot { commands.entity(id).despawn_recursive(); }
+    }
+
+    commands.entity(ship_id).insert(Invincible::at_least(ship_invincible, bomb::SHIP_INVINCIBILITY));
+    camera_effects.shake(bomb::TRAUMA);
+    camera_effects.flash(Palette::LightGold, bomb::FLASH);
+
+    // Wave of tiles spreading from the ship
+    let center = ship_body.center(ship_pos.translation) / tile_to_f32(1);
+    for y in 0..size::HEIGHT {
+        for x in 0..size::WIDTH {
+            let distance = Vec2::new(x as f32 + 0.5, y as f32 + 0.5).distance(center);
+            let glyph = if (x + y) % 2 == 0 { '*' } else { '+' };
+            let mut bundle = sprite(
+                glyph_index(glyph).unwrap(), x, y, z_pos::FLASH,
+                Palette::Transparent, if distance as usize % 2 == 0 { Palette::LightGold } else { Palette::Lava },
+                false, 0,
+                textures.mrmotext.clone(),
+            );
+            bundle.sprite.alpha = 0.;
+            commands
+                .spawn(bundle)
+                .insert(BombTile { delay: (distance * bomb::WAVE_FRAMES_PER_TILE) as usize, frame: 0 })
+                .insert(NoAnimation)
+                .insert(SurvivalUI);
+        }
+    }
+}
+
+pub fn update_bomb_tiles(
+    mut commands: Commands,
+    mut tiles: Query<(&mut BombTile, &mut TextModeTextureAtlasSprite, Entity)>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for (mut tile, mut sprite, id) in tiles.iter_mut() {
+        tile.frame += 1;
+        if tile.frame < tile.delay { continue; }
+
+        let t = tile.frame - tile.delay;
+        if t >= bomb::TILE_LIFETIME {
+            commands.entity(id).despawn_recursive();
+            continue;
+        }
+        sprite.alpha = 1. - t as f32 / bomb::TILE_LIFETIME as f32;
+    }
+}
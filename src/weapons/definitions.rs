@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use serde::Deserialize;
+use strum::IntoEnumIterator;
+
+use crate::collision::Hitbox;
+use crate::graphics::text::glyph_index;
+use crate::graphics::tiles::{Tile, Tiles};
+use crate::screens::Data;
+use crate::util;
+use crate::util::Palette;
+use crate::weapons::{Shot, Shots, Weapon, Weapons};
+
+/// Weapon definitions, loaded from `*.weapons.ron` files.
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "6f3c6b3e-5d0e-4a54-9a43-2d1f3c7f9b21"]
+pub struct WeaponDefinitions(pub Vec<WeaponDefinition>);
+
+#[derive(Deserialize, Clone)]
+pub struct WeaponDefinition {
+    pub model: Weapons,
+    pub kind: Shots,
+    pub tile: TileDefinition,
+    pub shot_tile: TileDefinition,
+    pub cooldown: u16,
+    pub name: char,
+    #[serde(default)]
+    pub charge: Option<u16>,
+    pub heat_per_shot: f32,
+    pub heat_dissipation: f32,
+    /// Shots fired from [LevelShots::level] until the next entry
+    pub shots: Vec<LevelShots>,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct TileDefinition {
+    pub tile: Tiles,
+    pub fg: Palette,
+}
+
+impl TileDefinition {
+    pub fn to_tile(&self) -> Tile {
+        self.tile.to_tile().with_fg(self.fg)
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct LevelShots {
+    pub level: u8,
+    pub shots: Vec<ShotDefinition>,
+}
+
+/// Shot description (left orientation)
+#[derive(Deserialize, Copy, Clone)]
+pub struct ShotDefinition {
+    pub speed: (f32, f32),
+    #[serde(default)]
+    pub piercing: bool,
+    #[serde(default = "default_damage")]
+    pub damage: i16,
+    #[serde(default)]
+    pub dx: f32,
+    #[serde(default)]
+    pub dy: f32,
+}
+
+fn default_damage() -> i16 { 1 }
+
+impl Into<Shot> for ShotDefinition {
+    fn into(self) -> Shot {
+        Shot {
+            piercing: self.piercing,
+            speed: Vec2::new(self.speed.0, self.speed.1),
+            damage: self.damage,
+            dx: self.dx,
+            dy: self.dy,
+            ..Shot::default()
+        }
+    }
+}
+
+impl WeaponDefinitions {
+    /// Checks that every weapon is defined once, its name glyph exists, its shot tile has a hitbox
+    /// and it fires shots from level 1.
+    pub fn validate(&self) -> Result<(), String> {
+        for model in Weapons::iter() {
+            match self.0.iter().filter(|d| d.model == model).count() {
+                0 => return Err(format!("{:?} isn't defined", model)),
+                1 => (),
+                _ => return Err(format!("{:?} is defined several times", model)),
+            }
+        }
+
+        for definition in self.0.iter() {
+            let model = definition.model;
+            if glyph_index(definition.name).is_none() {
+                return Err(format!("{:?}: no glyph for the name '{}'", model, definition.name));
+            }
+            let shot_tile = definition.shot_tile.to_tile();
+            if Hitbox::for_tile(shot_tile.index, shot_tile.bg == Palette::Transparent).is_none() {
+                return Err(format!("{:?}: the shot tile has no hitbox", model));
+            }
+            if !definition.shots.iter().any(|s| s.level <= 1 && !s.shots.is_empty()) {
+                return Err(format!("{:?}: no shots at level 1", model));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Validated weapon definitions, available once the game is loaded.
+#[derive(Resource)]
+pub struct Arsenal(HashMap<Weapons, WeaponDefinition>);
+
+impl FromWorld for Arsenal {
+    fn from_world(world: &mut World) -> Self {
+        let handle = world.resource::<Data>().weapons.clone();
+        let definitions = world
+            .resource::<Assets<WeaponDefinitions>>()
+            .get(&handle)
+            .expect("Weapon definitions aren't loaded.");
+        if let Err(e) = definitions.validate() {
+            panic!("Invalid weapon definitions: {}", e);
+        }
+        Arsenal(definitions.0.iter().map(|d| (d.model, d.clone())).collect())
+    }
+}
+
+impl Arsenal {
+    fn definition(&self, model: Weapons) -> &WeaponDefinition {
+        self.0.get(&model).expect("Validated definitions contain every weapon.")
+    }
+
+    /// Returns the weapon description for [level].
+    pub fn weapon(&self, model: Weapons, level: u8) -> Weapon {
+        let definition = self.definition(model);
+        let bonus = level.saturating_sub(1) as f32;
+        Weapon {
+            model,
+            shots: definition.kind,
+            tile: definition.tile.to_tile(),
+            shot_tile: definition.shot_tile.to_tile(),
+            cooldown: (definition.cooldown as f32 * (1. - util::fight::COOLDOWN_PER_LEVEL * bonus)).round() as u16,
+            name: definition.name,
+            level,
+            charge: definition.charge,
+            heat_per_shot: definition.heat_per_shot,
+            heat_dissipation: definition.heat_dissipation,
+        }
+    }
+
+    /// Returns the shots fired by [model] at [level].
+    pub fn shots(&self, model: Weapons, level: u8) -> Vec<Shot> {
+        let mut shots = self
+            .definition(model)
+            .shots
+            .iter()
+            .filter(|s| s.level <= level.max(1))
+            .max_by_key(|s| s.level)
+            .map_or(vec![], |s| s.shots.iter().map(|&shot| shot.into()).collect::<Vec<Shot>>());
+
+        let bonus = level.saturating_sub(1);
+        for shot in shots.iter_mut() {
+            shot.damage += (bonus / 2) as i16;
+            shot.speed.y *= 1. + util::fight::SPREAD_PER_LEVEL * bonus as f32;
+        }
+        shots
+    }
+}
@@ -0,0 +1,878 @@
+use std::f32::consts::PI;
+
+use bevy::math::vec2;
+use bevy::prelude::*;
+use bevy_text_mode::TextModeTextureAtlasSprite;
+use serde::Deserialize;
+use strum_macros::EnumIter;
+
+use crate::{GameState, MainBundle, util};
+use crate::characters::health;
+use crate::characters::health::{HitFlash, Recoil};
+use crate::characters::monsters::Monster;
+use crate::characters::ship::Ship;
+use crate::collision::{BodyType, CollisionSet, Contact, Damage, LastPosition, SingleContact, SolidBody};
+use crate::graphics::camera::CameraEffects;
+use crate::graphics::particles::{burst, Particles};
+use crate::graphics::tiles;
+use crate::graphics::tiles::{Tile, Tiles};
+use crate::progress::Progress;
+use crate::screens::Textures;
+use crate::util::{behaviors, camera, effects, is_oob, Palette, particles, Side, z_pos};
+use crate::util::size::{FRAME_BOTTOM, FRAME_TOP, tile_to_f32};
+
+pub use definitions::{Arsenal, WeaponDefinitions};
+
+mod bomb;
+mod definitions;
+
+/// Kind of shots
+#[derive(Copy, Clone, PartialEq, Eq, Deserialize)]
+pub enum Shots {
+    /// Straight line, 1 tile
+    Simple,
+    /// Straight shot, pierces through enemies
+    Piercing,
+    /// 2 shots, diagonal (45 deg)
+    Double,
+    /// Fill line (until obstacle) + follow player
+    Laser,
+    /// Several shots in a fan
+    Spread,
+    /// Turns toward the nearest monster
+    Homing,
+    /// Ricochets off the top and bottom of the frame
+    Bouncing,
+    /// Short range, pierces through enemies
+    Flame,
+}
+
+impl Shots {
+    fn destroy_on_contact(&self) -> bool {
+        match self {
+            Shots::Simple => true,
+            Shots::Piercing => false,
+            Shots::Double => true,
+            Shots::Laser => false,
+            Shots::Spread => true,
+            Shots::Homing => true,
+            Shots::Bouncing => true,
+            Shots::Flame => false,
+        }
+    }
+}
+
+/// Weapon description (left orientation)
+#[derive(Copy, Clone)]
+pub struct Weapon {
+    pub model: Weapons,
+    pub shots: Shots,
+    pub tile: Tile,
+    pub shot_tile: Tile,
+    pub cooldown: u16,
+    pub name: char,
+    pub level: u8,
+    /// Frames to fully charge the weapon by holding the fire key, None if the weapon can't be charged
+    pub charge: Option<u16>,
+    /// Heat added by each shot, the weapon overheats at 1
+    pub heat_per_shot: f32,
+    /// Heat removed each frame
+    pub heat_dissipation: f32,
+}
+
+impl Weapon {
+    /// Number of frames the laser fires.
+    pub fn laser_duration(&self) -> usize {
+        util::fight::LASER_FIRING + util::fight::LASER_FIRING_PER_LEVEL * self.level.saturating_sub(1) as usize
+    }
+
+    /// Maximum length of the laser beam, in tiles.
+    pub fn laser_length(&self) -> usize {
+        (util::ship::LASER_LENGTH + util::ship::LASER_LENGTH_PER_LEVEL * self.level.saturating_sub(1) as usize).min(util::size::WIDTH)
+    }
+
+    pub const fn get_solid_body() -> SolidBody {
+        SolidBody {
+            body_type: BodyType::Ghost,
+            width: tile_to_f32(1),
+            height: tile_to_f32(1),
+            bottom_right_anchor: false,
+        }
+    }
+}
+
+#[derive(Debug, EnumIter, Copy, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub enum Weapons {
+    Finger,
+    DoubleCannon,
+    Laser,
+    Spear,
+    Shotgun,
+    HomingMissile,
+    Bouncer,
+    Flamethrower,
+}
+
+/// Special attacks fired when swapping weapons with both weapons of a pair equipped.
+#[derive(Copy, Clone, Debug)]
+pub enum Combo {
+    /// Laser + Double cannon: fan of piercing shots
+    Prism,
+    /// Finger + Shotgun: half circle of shots on each side
+    Barrage,
+    /// Homing missile + Spear: volley of missiles
+    Swarm,
+}
+
+impl Combo {
+    pub fn of(a: Weapons, b: Weapons) -> Option<Self> {
+        match (a, b) {
+            (Weapons::Laser, Weapons::DoubleCannon) | (Weapons::DoubleCannon, Weapons::Laser) => Some(Combo::Prism),
+            (Weapons::Finger, Weapons::Shotgun) | (Weapons::Shotgun, Weapons::Finger) => Some(Combo::Barrage),
+            (Weapons::HomingMissile, Weapons::Spear) | (Weapons::Spear, Weapons::HomingMissile) => Some(Combo::Swarm),
+            _ => None,
+        }
+    }
+
+    /// Weapon giving its shot tile and behavior to the combo shots
+    fn weapon(&self) -> Weapons {
+        match self {
+            Combo::Prism => Weapons::DoubleCannon,
+            Combo::Barrage => Weapons::Shotgun,
+            Combo::Swarm => Weapons::HomingMissile,
+        }
+    }
+
+    /// Shots fired on each side (left orientation)
+    fn get_shots(&self) -> Vec<Shot> {
+        match self {
+            Combo::Prism => (-3..=3)
+                .map(|i| Shot { piercing: true, speed: Vec2::new(-1., 0.2 * i as f32), damage: 2, ..Shot::default() })
+                .collect(),
+            Combo::Barrage => (0..7)
+                .map(|i| PI / 2. + PI * i as f32 / 6.)
+                .map(|angle| Shot { speed: Vec2::from_angle(angle), ..Shot::default() })
+                .collect(),
+            Combo::Swarm => vec![
+                Shot { speed: Vec2::new(-0.5, 0.5), dy: 3., damage: 2, ..Shot::default() },
+                Shot { speed: Vec2::new(-0.7, 0.2), dy: 1., damage: 2, ..Shot::default() },
+                Shot { speed: Vec2::new(-0.7, -0.2), dy: -1., damage: 2, ..Shot::default() },
+                Shot { speed: Vec2::new(-0.5, -0.5), dy: -3., damage: 2, ..Shot::default() },
+            ],
+        }
+    }
+}
+
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<WeaponChanged>()
+            .insert_resource(WeaponSwap::default())
+            .add_systems((update_swap, bomb::drop_bomb, bomb::update_bomb_tiles).in_set(OnUpdate(GameState::Survival)))
+            .add_systems(
+                (update_weapons, shoot, cool_weapons.after(shoot), update_charge_visual.after(shoot), update_shots, collide_shot.in_set(CollisionSet::Resolve), update_laser_shots,
+                 steer_homing_shots.before(update_shots), bounce_shots.before(update_shots), update_shots_lifetime,
+                 switch_weapons.after(tiles::flip))
+                    .in_set(OnUpdate(GameState::Survival))
+            )
+            .add_system(cleanup.in_schedule(OnExit(GameState::Survival)));
+    }
+}
+
+#[derive(Component)]
+struct WeaponsUI;
+
+#[derive(Component)]
+pub struct ActiveWeapon {
+    pub side: Side,
+    pub weapon: Weapon,
+}
+
+pub struct WeaponChanged(pub Side, pub Weapon);
+
+pub enum LaserState {
+    Loading,
+    Firing
+}
+
+#[derive(Component)]
+pub struct LaserShot {
+    pub ref_entity: Entity,
+    pub offset: Vec2,
+    pub state: LaserState,
+    pub frame: usize,
+    /// Number of frames the laser fires
+    pub duration: usize,
+    /// Maximum length of the beam, in tiles
+    pub length: usize,
+    /// Current length of the beam, in tiles (0 until the laser fires)
+    pub beam: usize,
+}
+
+/// Stops laser beams, which are truncated at the body (shielded monsters…).
+#[derive(Component)]
+pub struct BlocksLasers;
+
+#[derive(Component)]
+pub struct JustFired(u16);
+
+/// Weapon heat, the weapon can't fire while [overheated] is positive.
+#[derive(Component, Default)]
+pub struct Heat {
+    pub value: f32,
+    pub overheated: u16,
+}
+
+/// Weapon swap state, weapons can't be swapped while [cooldown] is positive.
+#[derive(Resource, Default)]
+pub struct WeaponSwap {
+    pub cooldown: u16,
+}
+
+/// The next shot of the weapon is empowered, if fired within the given number of frames.
+#[derive(Component)]
+pub struct SwapBonus(u16);
+
+/// Frames the fire key has been held since the last shot, for weapons with a [Weapon::charge].
+#[derive(Component)]
+pub struct Charging(u16);
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum HomingTarget {
+    /// Nearest alive monster on the side of the shot
+    Monsters,
+    Ship,
+}
+
+/// Shot turning toward a target, by [turn_rate] radians at most each frame.
+/// A new target is acquired when the current one disappears.
+#[derive(Component)]
+pub struct Homing {
+    pub target_kind: HomingTarget,
+    pub target: Option<Entity>,
+    pub turn_rate: f32,
+}
+
+impl Homing {
+    pub fn monsters() -> Self {
+        Self { target_kind: HomingTarget::Monsters, target: None, turn_rate: util::fight::HOMING_TURN_RATE }
+    }
+
+    pub fn ship() -> Self {
+        Self { target_kind: HomingTarget::Ship, target: None, turn_rate: util::fight::HOMING_TURN_RATE }
+    }
+}
+
+/// Shot ricocheting off the top and bottom of the frame.
+#[derive(Component)]
+pub struct Bouncing;
+
+/// Frames before the shot disappears.
+#[derive(Component)]
+pub struct ShotLifetime(pub usize);
+
+#[derive(Component, Copy, Clone)]
+pub struct Shot {
+    piercing: bool,
+    side: Side,
+    speed: Vec2,
+    damage: i16,
+    dy: f32,
+    dx: f32,
+}
+
+impl Default for Shot {
+    fn default() -> Self {
+        Self {
+            piercing: false,
+            side: Side::Left,
+            speed: Default::default(),
+            damage: 1,
+            dy: 0.0,
+            dx: 0.0,
+        }
+    }
+}
+
+impl Shot {
+    fn with_side(&mut self, side: Side) -> Self {
+        if side == Side::Left { *self } else {
+            let speed = Vec2::new(self.speed.x * -1., self.speed.y);
+            Self { side, speed, ..*self }
+        }
+    }
+
+    /// Direction of the shot along x, shots without horizontal speed (lasers) go the way their weapon faces.
+    pub fn direction(&self) -> f32 {
+        if self.speed.x != 0. { self.speed.x.signum() } else { -self.side.to_sign_f32() }
+    }
+}
+
+pub fn spawn_weapon(
+    weapon: Weapons,
+    level: u8,
+    side: Side,
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    arsenal: &Arsenal,
+    weapon_changed: &mut EventWriter<WeaponChanged>,
+) {
+    let mut weapon = arsenal.weapon(weapon, level);
+    if side == Side::Right { weapon.tile.flip = !weapon.tile.flip };
+    commands
+        .spawn(ActiveWeapon { side, weapon })
+        .insert(Heat::default())
+        .insert(Weapon::get_solid_body())
+        .insert(MainBundle::from_xyz(0., 0., z_pos::WEAPONS))
+        .insert(WeaponsUI)
+        .with_children(|spawn| { spawn.spawn(weapon.tile.sprite(0, 0, 0., atlas)); });
+    weapon_changed.send(WeaponChanged(side, weapon));
+}
+
+/// Returns the position of the weapon on [side] of the ship.
+fn weapon_translation(ship: &Transform, side: Side) -> Vec3 {
+    Vec3::new(
+        ship.translation.x + if side == Side::Left { -2. } else { tile_to_f32(3) + 2. },
+        ship.translation.y + tile_to_f32(2),
+        z_pos::WEAPONS,
+    )
+}
+
+fn update_weapons(
+    mut commands: Commands,
+    ship: Query<(&Transform, &Visibility), With<Ship>>,
+    mut weapons: Query<(&ActiveWeapon, Option<&mut JustFired>, &mut Transform, &mut Visibility, Entity), Without<Ship>>,
+) {
+    let (ship_pos, ship_vis) = ship.single();
+
+    for (weapon, just_fired, mut pos, mut vis, id) in weapons.iter_mut() {
+        let &ActiveWeapon { side, weapon: Weapon { cooldown, .. } } = weapon;
+        pos.translation = weapon_translation(ship_pos, side);
+        vis.set_if_neq(*ship_vis);
+        if let Some(mut just_fired) = just_fired {
+            if just_fired.0 <= cooldown / 2 { pos.translation.x += side.to_sign_f32(); }
+            just_fired.0 += 1;
+            if just_fired.0 >= cooldown {
+                commands.entity(id).remove::<JustFired>();
+            }
+        }
+    }
+}
+
+fn switch_weapons(
+    mut commands: Commands,
+    mut weapons: Query<(&mut ActiveWeapon, Entity), With<SolidBody>>,
+    laser_shots: Query<Entity, With<LaserShot>>,
+    ship: Query<(&Transform, Entity), (With<Ship>, With<SolidBody>)>,
+    keys: Res<Input<KeyCode>>,
+    mut swap: ResMut<WeaponSwap>,
+    mut weapon_changed: EventWriter<WeaponChanged>,
+    textures: Res<Textures>,
+    arsenal: Res<Arsenal>,
+    progress: Res<Progress>,
+) {
+    // Weapons can't be swapped while the ship is wrecked or respawning
+    let Ok((ship_pos, ship_id)) = ship.get_single() else { return; };
+    if keys.just_pressed(KeyCode::Space) && swap.cooldown == 0 {
+        swap.cooldown = util::fight::SWAP_COOLDOWN;
+
+        for (mut active_weapon, e) in weapons.iter_mut() {
+            let old_side = active_weapon.side;
+            let new_side = old_side.flip();
+            active_weapon.side = new_side;
+            weapon_changed.send(WeaponChanged(new_side, active_weapon.weapon));
+            commands.entity(e).insert(tiles::Flip).insert(SwapBonus(util::fight::SWAP_BONUS_WINDOW));
+        }
+
+        for e in laser_shots.iter() {
+            commands.entity(e).despawn_recursive()
+        }
+
+        // Combined attack
+        let equipped = weapons.iter().map(|(w, _)| (w.side, w.weapon)).collect::<Vec<(Side, Weapon)>>();
+        let [(_, a), (_, b)] = equipped[..] else { return; };
+        let Some(combo) = Combo::of(a.model, b.model) else { return; };
+        let weapon = arsenal.weapon(combo.weapon(), a.level.max(b.level));
+        for (side, _) in equipped {
+            let pos = Transform::from_translation(weapon_translation(ship_pos, side));
+            for &(mut shot) in combo.get_shots().iter() {
+                shot.damage = (shot.damage as f32 * progress.damage_multiplier).round() as i16;
+                spawn_shot(shot, 1, &mut commands, &textures, side, weapon, &pos, Some(ship_id));
+            }
+        }
+    }
+}
+
+fn update_swap(
+    mut commands: Commands,
+    mut swap: ResMut<WeaponSwap>,
+    mut bonuses: Query<(&mut SwapBonus, Entity)>,
+) {
+    if swap.cooldown > 0 { swap.cooldown -= 1; }
+
+    for (mut bonus, id) in bonuses.iter_mut() {
+        if bonus.0 == 0 { commands.entity(id).remove::<SwapBonus>(); }
+        else { bonus.0 -= 1; }
+    }
+}
+
+fn shoot(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut weapons: Query<(&ActiveWeapon, Option<&JustFired>, Option<&mut Charging>, Option<&SwapBonus>, &mut Heat, &Transform, Entity), Without<Ship>>,
+    ship: Query<Entity, (With<Ship>, With<SolidBody>)>,
+    textures: Res<Textures>,
+    progress: Res<Progress>,
+    arsenal: Res<Arsenal>,
+) {
+    // Weapons are silent while the ship is wrecked or respawning
+    let Ok(ship) = ship.get_single() else { return; };
+    for (key_code, side) in [(KeyCode::Left, Side::Left), (KeyCode::Right, Side::Right)] {
+        let pressed = keys.pressed(key_code);
+        for (&ActiveWeapon { side: weapon_side, weapon }, just_fired, charging, swap_bonus, mut heat, pos, id) in weapons.iter_mut() {
+            if weapon_side != side { continue; }
+            if heat.overheated > 0 {
+                if charging.is_some() { commands.entity(id).remove::<Charging>(); }
+                continue;
+            }
+
+            let empowered = match (charging, weapon.charge) {
+                // Holding: charge the weapon
+                (Some(mut charging), Some(full_charge)) if pressed => {
+                    charging.0 = (charging.0 + 1).min(full_charge);
+                    continue;
+                }
+                // Released: fire an empowered shot if the weapon is fully charged
+                (Some(charging), Some(full_charge)) => {
+                    commands.entity(id).remove::<Charging>();
+                    if charging.0 < full_charge { continue; }
+                    true
+                }
+                _ if pressed && just_fired.is_none() => {
+                    if weapon.charge.is_some() { commands.entity(id).insert(Charging(0)); }
+                    if swap_bonus.is_some() { commands.entity(id).remove::<SwapBonus>(); }
+                    swap_bonus.is_some()
+                }
+                _ => continue,
+            };
+
+            commands.entity(id).insert(JustFired(0));
+            heat.value += weapon.heat_per_shot;
+            if heat.value >= 1. {
+                heat.value = 1.;
+                heat.overheated = util::fight::OVERHEAT_PENALTY;
+            }
+
+            for &(mut shot) in arsenal.shots(weapon.model, weapon.level).iter() {
+                shot.damage = (shot.damage as f32 * progress.damage_multiplier).round() as i16;
+                if empowered {
+                    shot.damage *= util::fight::CHARGE_DAMAGE_MULTIPLIER;
+                    shot.piercing = true;
+                }
+                let size = if empowered { util::fight::CHARGED_SHOT_SIZE } else { 1 };
+                spawn_shot(shot, size, &mut commands, &textures, side, weapon, pos, Some(ship));
+            }
+        }
+    }
+}
+
+fn cool_weapons(
+    mut weapons: Query<(&ActiveWeapon, &mut Heat)>,
+) {
+    for (weapon, mut heat) in weapons.iter_mut() {
+        if heat.overheated > 0 {
+            heat.overheated -= 1;
+            if heat.overheated == 0 { heat.value = 0.; }
+        } else if heat.value > 0. {
+            heat.value = (heat.value - weapon.weapon.heat_dissipation).max(0.);
+        }
+    }
+}
+
+fn update_charge_visual(
+    weapons: Query<(&ActiveWeapon, Option<&Charging>, Option<&SwapBonus>, &Children)>,
+    mut sprites: Query<&mut TextModeTextureAtlasSprite>,
+) {
+    for (&ActiveWeapon { weapon, .. }, charging, swap_bonus, children) in weapons.iter() {
+        let fg = match (charging, weapon.charge) {
+            (Some(&Charging(charge)), Some(full_charge)) if charge >= full_charge => effects::FLASH_COLOR,
+            (Some(&Charging(charge)), Some(full_charge)) if charge >= util::fight::CHARGE_VISUAL_DELAY => {
+                // Blink faster as the charge increases
+                let interval = (12 - 10 * charge / full_charge).max(2);
+                if (charge / interval) % 2 == 0 { effects::FLASH_COLOR } else { weapon.tile.fg }
+            }
+            _ if swap_bonus.is_some() => effects::FLASH_COLOR,
+            _ => weapon.tile.fg,
+        };
+
+        for &child in children.iter() {
+            let Ok(mut sprite) = sprites.get_mut(child) else { continue };
+            let fg: Color = fg.into();
+            if sprite.fg != fg { sprite.fg = fg; }
+        }
+    }
+}
+
+fn spawn_shot(
+    shot: Shot,
+    size: usize,
+    commands: &mut Commands,
+    textures: &Res<Textures>,
+    side: Side,
+    weapon: Weapon,
+    pos: &Transform,
+    ship: Option<Entity>,
+) {
+    let mut shot = shot.clone();
+    // Bigger shots grow away from the weapon, centered vertically
+    let bundle = MainBundle::from_xyz(
+        pos.translation.x - (tile_to_f32(1) + shot.dx) * side.to_sign_f32() - side.on_left_right(tile_to_f32(size - 1), 0.),
+        pos.translation.y + shot.dy - tile_to_f32(size - 1) / 2.,
+        z_pos::SHOTS);
+    let start = bundle.transform.translation;
+    burst(commands, &textures.mrmotext, Particles::MuzzleFlash.particle().with_side(side), start.truncate(), particles::MUZZLE_FLASH);
+    let mut entity_commands = commands.spawn(shot.with_side(side));
+    let entity_commands = entity_commands
+        .insert(bundle)
+        .insert(Damage(shot.damage))
+        .insert(WeaponsUI)
+        .with_children(|spawn| {
+            let mut tile = weapon.shot_tile;
+            if side == Side::Right { tile = tile.flip(); }
+            for x in 0..size {
+                for y in 0..size {
+                    spawn.spawn(tile.sprite(x, y, 0., &textures.mrmotext));
+                }
+            }
+        });
+
+    if weapon.shots == Shots::Laser && ship.is_some() {
+        entity_commands
+            .insert(LaserShot {
+                ref_entity: ship.unwrap(),
+                offset: Vec2::new(if side == Side::Left { -tile_to_f32(1) - 1. } else { tile_to_f32(4) + 1. }, tile_to_f32(2)),
+                state: LaserState::Loading,
+                frame: 0,
+                duration: weapon.laser_duration(),
+                length: weapon.laser_length(),
+                beam: 0,
+            });
+    } else {
+        entity_commands
+            .insert(SolidBody {
+                body_type: BodyType::ShipShot,
+                width: tile_to_f32(size),
+                height: tile_to_f32(size),
+                bottom_right_anchor: false,
+            })
+            .insert(LastPosition(start));
+        if !shot.piercing { entity_commands.insert(SingleContact); }
+    }
+
+    match weapon.shots {
+        Shots::Homing => { entity_commands.insert(Homing::monsters()).insert(ShotLifetime(util::fight::HOMING_LIFETIME)); }
+        Shots::Bouncing => { entity_commands.insert(Bouncing); }
+        Shots::Flame => { entity_commands.insert(ShotLifetime(util::fight::FLAME_LIFETIME)); }
+        _ => {}
+    }
+}
+
+/// Spawns an enemy shot at [origin] homing on the ship, fired by a monster coming from [side].
+pub fn spawn_enemy_homing_shot(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    origin: Vec2,
+    side: Side,
+) {
+    let shot = Shot {
+        side,
+        speed: vec2(side.to_sign_f32() * behaviors::SEEKER_SPEED, 0.),
+        ..default()
+    };
+    let tile = Tiles::Dot.to_tile().with_fg(behaviors::SEEKER_COLOR);
+    commands
+        .spawn(shot)
+        .insert(MainBundle::from_xyz(origin.x.round(), origin.y.round(), z_pos::SHOTS))
+        .insert(SolidBody {
+            body_type: BodyType::EnemyShot,
+            width: tile_to_f32(1),
+            height: tile_to_f32(1),
+            bottom_right_anchor: false,
+        })
+        .insert(Damage(shot.damage))
+        .insert(SingleContact)
+        .insert(Homing::ship())
+        .insert(ShotLifetime(util::fight::HOMING_LIFETIME))
+        .insert(WeaponsUI)
+        .with_children(|builder| { builder.spawn(tile.sprite(0, 0, 0., atlas)); });
+}
+
+fn update_shots(
+    mut commands: Commands,
+    mut shots: Query<(&Shot, &mut Transform, Option<&mut LastPosition>, Entity), Without<LaserShot>>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for (shot, mut transform, last_position, id) in shots.iter_mut() {
+        if let Some(mut last_position) = last_position { last_position.0 = transform.translation; }
+        transform.translation.x += shot.speed.x;
+        transform.translation.y += shot.speed.y;
+
+        if is_oob(&transform) { commands.entity(id).despawn_recursive(); }
+    }
+}
+
+/// Rotates [speed] toward [direction], by [max_angle] radians at most.
+fn turn_toward(speed: Vec2, direction: Vec2, max_angle: f32) -> Vec2 {
+    let angle = speed.angle_between(direction);
+    if !angle.is_finite() { return speed; }
+    Vec2::from_angle(angle.clamp(-max_angle, max_angle)).rotate(speed)
+}
+
+/// Returns the center of [target], or None if it isn't a valid target anymore.
+fn target_center(
+    kind: HomingTarget,
+    target: Entity,
+    monsters: &Query<(&Monster, &SolidBody, &Transform, Entity)>,
+    ship: &Query<(&SolidBody, &Transform, Entity), With<Ship>>,
+) -> Option<Vec2> {
+    match kind {
+        HomingTarget::Monsters => monsters
+            .get(target).ok()
+            .filter(|(monster, ..)| monster.lives > 0)
+            .map(|(_, body, pos, _)| body.center(pos.translation)),
+        HomingTarget::Ship => ship
+            .get(target).ok()
+            .map(|(body, pos, _)| body.center(pos.translation)),
+    }
+}
+
+/// Returns the nearest valid target from [pos].
+fn acquire_target(
+    kind: HomingTarget,
+    side: Side,
+    pos: Vec2,
+    monsters: &Query<(&Monster, &SolidBody, &Transform, Entity)>,
+    ship: &Query<(&SolidBody, &Transform, Entity), With<Ship>>,
+) -> Option<Entity> {
+    let candidates: Vec<(Vec2, Entity)> = match kind {
+        HomingTarget::Monsters => monsters
+            .iter()
+            .filter(|(monster, ..)| monster.lives > 0 && monster.side == side)
+            .map(|(_, body, pos, id)| (body.center(pos.translation), id))
+            .collect(),
+        HomingTarget::Ship => ship
+            .iter()
+            .map(|(body, pos, id)| (body.center(pos.translation), id))
+            .collect(),
+    };
+    candidates
+        .into_iter()
+        .min_by(|(a, _), (b, _)| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)))
+        .map(|(_, id)| id)
+}
+
+fn steer_homing_shots(
+    mut shots: Query<(&mut Shot, &mut Homing, &Transform)>,
+    monsters: Query<(&Monster, &SolidBody, &Transform, Entity)>,
+    ship: Query<(&SolidBody, &Transform, Entity), With<Ship>>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for (mut shot, mut homing, pos) in shots.iter_mut() {
+        let pos = pos.translation.truncate();
+
+        let mut target = homing.target.and_then(|id| target_center(homing.target_kind, id, &monsters, &ship));
+        if target.is_none() {
+            homing.target = acquire_target(homing.target_kind, shot.side, pos, &monsters, &ship);
+            target = homing.target.and_then(|id| target_center(homing.target_kind, id, &monsters, &ship));
+        }
+
+        let Some(target) = target else { continue };
+        shot.speed = turn_toward(shot.speed, target - pos, homing.turn_rate);
+    }
+}
+
+fn bounce_shots(
+    mut shots: Query<(&mut Shot, &Transform), With<Bouncing>>,
+) {
+    for (mut shot, pos) in shots.iter_mut() {
+        let y = pos.translation.y;
+        if (y < tile_to_f32(FRAME_BOTTOM) && shot.speed.y < 0.)
+            || (y + tile_to_f32(1) > tile_to_f32(FRAME_TOP) && shot.speed.y > 0.) {
+            shot.speed.y = -shot.speed.y;
+        }
+    }
+}
+
+fn update_shots_lifetime(
+    mut commands: Commands,
+    mut shots: Query<(&mut ShotLifetime, Entity)>,
+    camera_effects: Res<CameraEffects>,
+) {
+    if camera_effects.skip_frame() { return; }
+    for (mut lifetime, id) in shots.iter_mut() {
+        if lifetime.0 == 0 { commands.entity(id).despawn_recursive(); }
+        else { lifetime.0 -= 1; }
+    }
+}
+
+/// Returns the number of tiles of a beam starting at [origin] (bottom left of the first tile)
+/// needed to reach the nearest [obstacles], [max_length] if none is in the way.
+fn beam_length<'a>(
+    origin: Vec2,
+    side: Side,
+    max_length: usize,
+    obstacles: impl Iterator<Item=(&'a SolidBody, Vec3)>,
+) -> usize {
+    obstacles
+        .filter_map(|(body, translation)| {
+            let bottom_left = body.bottom_left(translation);
+            // Obstacle on the same line as the beam
+            if bottom_left.y >= origin.y + tile_to_f32(1) || bottom_left.y + body.height <= origin.y { return None; }
+            // Distance from the start of the beam to the near and far edges of the obstacle
+            let (near, far) = if side == Side::Left {
+                (origin.x + tile_to_f32(1) - (bottom_left.x + body.width), origin.x + tile_to_f32(1) - bottom_left.x)
+            } else {
+                (bottom_left.x - origin.x, bottom_left.x + body.width - origin.x)
+            };
+            if far <= 0. { return None; }
+            Some((near.max(0.) / tile_to_f32(1)).floor() as usize + 1)
+        })
+        .fold(max_length, usize::min)
+        .max(1)
+}
+
+fn update_laser_shots(
+    mut commands: Commands,
+    mut shots: Query<(&mut LaserShot, &Shot, &mut Transform, Entity)>,
+    children_query: Query<&Children>,
+    ref_entity: Query<(&Transform, Option<&Ship>), Without<LaserShot>>,
+    obstacles: Query<(&SolidBody, &Transform), (With<BlocksLasers>, Without<LaserShot>)>,
+    textures: Res<Textures>,
+    mut camera_effects: ResMut<CameraEffects>,
+) {
+    for (mut laser, shot, mut pos, id) in shots.iter_mut() {
+        let Ok((ref_transform, ship)) = ref_entity.get(laser.ref_entity) else {
+            commands.entity(id).despawn_recursive();
+            continue;
+        };
+
+        // Update laser position (relative to ref_entity)
+        pos.translation.x = ref_transform.translation.x + laser.offset.x + shot.dx;
+        pos.translation.y = ref_transform.translation.y + laser.offset.y + shot.dy;
+        pos.translation.z = z_pos::SHOTS;
+        // Lasers keep following their weapon in slow motion
+        if camera_effects.skip_frame() { continue; }
+
+        // Update laser state
+        laser.frame += 1;
+        match laser.state {
+            // Fire laser
+            LaserState::Loading if laser.frame >= util::fight::LASER_LOADING => {
+                laser.frame = 0;
+                laser.state = LaserState::Firing;
+                camera_effects.shake(camera::LASER_TRAUMA);
+                camera_effects.punch(camera::LASER_ZOOM);
+            }
+
+            // Destroy laser
+            LaserState::Firing if laser.frame >= laser.duration => {
+                commands.entity(id).despawn_recursive();
+                continue;
+            }
+
+            // Sparks along the beam
+            LaserState::Firing if laser.frame % particles::LASER_SPARK_INTERVAL == 0 => {
+                let x = tile_to_f32(laser.beam) * rand::random::<f32>() * if shot.side == Side::Left { -1. } else { 1. };
+                burst(&mut commands, &textures.mrmotext, Particles::LaserSpark.particle(), pos.translation.truncate() + Vec2::new(x, 0.), 1);
+            }
+            _ => ()
+        }
+
+        let LaserState::Firing = laser.state else { continue };
+
+        // Truncate the beam at the nearest obstacle
+        let length = beam_length(
+            pos.translation.truncate(), shot.side, laser.length,
+            obstacles.iter().map(|(body, transform)| (body, transform.translation)),
+        );
+        if length == laser.beam { continue; }
+        laser.beam = length;
+
+        // Delete children
+        for child in children_query.iter_descendants(id) {
+            commands.entity(child).despawn_recursive();
+        }
+
+        // Add body and children
+        commands
+            .entity(id)
+            .insert(SolidBody {
+                body_type: if ship.is_some() { BodyType::ShipShot } else { BodyType::EnemyShot },
+                width: tile_to_f32(length),
+                height: tile_to_f32(1),
+                bottom_right_anchor: shot.side == Side::Left,
+            })
+            .with_children(|builder| {
+                let tile = Tiles::Laser.to_tile().with_fg(Palette::LightRed);
+                for x in 0..length {
+                    let mut bundle = tile.sprite(0, 0, 0., &textures.mrmotext);
+                    bundle.transform.translation.x = tile_to_f32(x) * if shot.side == Side::Left { -1. } else { 1. };
+                    builder.spawn(bundle);
+                }
+            });
+    }
+}
+
+fn collide_shot(
+    mut commands: Commands,
+    mut contact: EventReader<Contact>,
+    shot_info: Query<&Shot>,
+) {
+    for contact in contact.iter() {
+        let Some((shot, _)) = contact.between(BodyType::ShipShot, BodyType::Enemy)
+            .or_else(|| contact.between(BodyType::EnemyShot, BodyType::Ship)) else { continue };
+        let Ok(shot_info) = shot_info.get(shot.entity) else { continue };
+        if !shot_info.piercing {
+            commands.entity(shot.entity).despawn_recursive();
+        }
+    }
+}
+
+pub fn monster_looses_life(
+    mut commands: Commands,
+    mut contact: EventReader<Contact>,
+    mut monsters: Query<(&mut Monster, &SolidBody, &Transform)>,
+    shots: Query<&Shot>,
+    textures: Res<Textures>,
+) {
+    for contact in contact.iter() {
+        let Some((enemy, shot)) = contact.between(BodyType::Enemy, BodyType::ShipShot) else { continue };
+        if let Ok((mut monster, body, pos)) = monsters.get_mut(enemy.entity) {
+            let direction = shots.get(shot.entity).map_or(0., Shot::direction);
+            // Blocked shots only spark (see [crate::graphics::particles])
+            if monster.blocks(direction) { continue; }
+            let damage = monster.damage_taken(shot.damage, direction);
+            monster.lives -= damage;
+            let top = body.bottom_left(pos.translation) + vec2(body.width / 2., body.height);
+            health::spawn_damage_number(&mut commands, &textures.mrmotext, damage, top);
+
+            let killing = monster.lives <= 0;
+            commands.entity(enemy.entity)
+                .insert(HitFlash::new(killing))
+                .insert(Recoil::new(direction, killing));
+        }
+    }
+}
+
+fn cleanup(
+    mut commands: Commands,
+    query: Query<Entity, With<WeaponsUI>>,
+    mut swap: ResMut<WeaponSwap>,
+) {
+    for e in &query { commands.entity(e).despawn_recursive(); }
+    swap.cooldown = 0;
+}
\ No newline at end of file